@@ -1,23 +1,29 @@
 use crate::r2::client::R2Client;
+use crate::r2::pool::ClientPool;
+use crate::r2::types::{EndpointConfig, R2Error};
 use crate::storage::config::{Account, ConfigStore};
 use serde::{Deserialize, Serialize};
+use tauri::State;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AccountInfo {
     pub id: String,
     pub name: String,
     pub account_id: String,
+    pub endpoint: EndpointConfig,
 }
 
 #[tauri::command]
 pub async fn save_account(
+    pool: State<'_, ClientPool>,
     id: String,
     name: String,
     account_id: String,
     access_key_id: String,
     secret_access_key: String,
-) -> Result<(), String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
+    endpoint: Option<EndpointConfig>,
+) -> Result<(), R2Error> {
+    let store = ConfigStore::new()?;
 
     let account = Account {
         id,
@@ -25,15 +31,20 @@ pub async fn save_account(
         account_id,
         access_key_id,
         secret_access_key,
+        endpoint: endpoint.unwrap_or_default(),
+        secret_in_vault: false,
     };
 
-    store.save_account(&account).map_err(|e| e.to_string())
+    store.save_account(&account)?;
+
+    pool.invalidate(&account.id);
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn get_accounts() -> Result<Vec<AccountInfo>, String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let accounts = store.get_accounts().map_err(|e| e.to_string())?;
+pub async fn get_accounts() -> Result<Vec<AccountInfo>, R2Error> {
+    let store = ConfigStore::new()?;
+    let accounts = store.get_accounts()?;
 
     Ok(accounts
         .into_iter()
@@ -41,14 +52,18 @@ pub async fn get_accounts() -> Result<Vec<AccountInfo>, String> {
             id: a.id,
             name: a.name,
             account_id: a.account_id,
+            endpoint: a.endpoint,
         })
         .collect())
 }
 
 #[tauri::command]
-pub async fn delete_account(id: String) -> Result<(), String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    store.delete_account(&id).map_err(|e| e.to_string())
+pub async fn delete_account(pool: State<'_, ClientPool>, id: String) -> Result<(), R2Error> {
+    let store = ConfigStore::new()?;
+    store.delete_account(&id)?;
+
+    pool.invalidate(&id);
+    Ok(())
 }
 
 #[tauri::command]
@@ -56,14 +71,21 @@ pub async fn validate_credentials(
     account_id: String,
     access_key_id: String,
     secret_access_key: String,
-) -> Result<bool, String> {
-    let client = R2Client::new(&account_id, &access_key_id, &secret_access_key)
-        .await
-        .map_err(|e| e.to_string())?;
+    endpoint: Option<EndpointConfig>,
+) -> Result<bool, R2Error> {
+    let network = ConfigStore::new()?.get_settings()?.network;
+    let client = R2Client::new(
+        &account_id,
+        &access_key_id,
+        &secret_access_key,
+        &endpoint.unwrap_or_default(),
+        &network,
+    )
+    .await?;
 
     // Try to list buckets to validate credentials
     match client.list_buckets().await {
         Ok(_) => Ok(true),
-        Err(e) => Err(format!("凭证验证失败: {}", e)),
+        Err(e) => Err(e),
     }
 }
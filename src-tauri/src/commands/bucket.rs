@@ -1,91 +1,202 @@
-use crate::r2::client::R2Client;
-use crate::r2::types::BucketInfo;
-use crate::storage::config::ConfigStore;
+use crate::i18n::{t, tf};
+use crate::r2::client;
+use crate::r2::cors::{self, CorsRule};
+use crate::r2::lifecycle::{LifecyclePreview, LifecycleRule};
+use crate::r2::pool::ClientPool;
+use crate::r2::types::{BucketInfo, CreateBucketOptions, R2Error};
+use crate::transfer::progress::BatchTracker;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
-pub async fn list_buckets(account_id: String) -> Result<Vec<BucketInfo>, String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let account = store
-        .get_account(&account_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("账户不存在")?;
-
-    let client = R2Client::new(
-        &account.account_id,
-        &account.access_key_id,
-        &account.secret_access_key,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+pub async fn list_buckets(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+) -> Result<Vec<BucketInfo>, R2Error> {
+    let client = pool.get(&account_id).await?;
 
-    client.list_buckets().await.map_err(|e| e.to_string())
+    client.list_buckets().await
 }
 
 #[tauri::command]
-pub async fn create_bucket(account_id: String, bucket_name: String) -> Result<(), String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let account = store
-        .get_account(&account_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("账户不存在")?;
-
-    let client = R2Client::new(
-        &account.account_id,
-        &account.access_key_id,
-        &account.secret_access_key,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+pub async fn create_bucket(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    options: Option<CreateBucketOptions>,
+) -> Result<(), R2Error> {
+    let options = options.unwrap_or_default();
+    let client = match options.jurisdiction {
+        Some(jurisdiction) => pool.get_for_jurisdiction(&account_id, jurisdiction).await?,
+        None => pool.get(&account_id).await?,
+    };
 
     client
-        .create_bucket(&bucket_name)
+        .create_bucket(&bucket_name, options.location_hint)
         .await
-        .map_err(|e| e.to_string())
 }
 
+// Lets the create dialog flag a bad name as it is typed
 #[tauri::command]
-pub async fn delete_bucket(account_id: String, bucket_name: String) -> Result<(), String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let account = store
-        .get_account(&account_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("账户不存在")?;
+pub async fn validate_bucket_name(bucket_name: String) -> Result<(), R2Error> {
+    client::validate_bucket_name(&bucket_name)
+}
 
-    let client = R2Client::new(
-        &account.account_id,
-        &account.access_key_id,
-        &account.secret_access_key,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+// With `force`, the bucket is emptied first; progress goes out on `batch-progress`
+#[tauri::command]
+pub async fn delete_bucket(
+    app: AppHandle,
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    force: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<(), R2Error> {
+    let client = pool.get(&account_id).await?;
 
-    client
-        .delete_bucket(&bucket_name)
-        .await
-        .map_err(|e| e.to_string())
+    if force.unwrap_or(false) {
+        let operation_id = operation_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let tracker = BatchTracker::new(app, operation_id);
+        let emptied = client.empty_bucket(&bucket_name, &tracker).await;
+        tracker.finish();
+
+        let failed = emptied?.failed.len();
+        if failed > 0 {
+            return Err(R2Error::InvalidInput(tf("bucket.not_emptied", &[&failed])));
+        }
+    }
+
+    client.delete_bucket(&bucket_name).await
 }
 
 #[tauri::command]
 pub async fn get_bucket_info(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+) -> Result<BucketInfo, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    client.get_bucket_info(&bucket_name).await
+}
+
+#[tauri::command]
+pub async fn get_bucket_cors(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+) -> Result<Vec<CorsRule>, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    client.get_bucket_cors(&bucket_name).await
+}
+
+// Returns the rules as saved, after normalization
+#[tauri::command]
+pub async fn put_bucket_cors(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    rules: Vec<CorsRule>,
+) -> Result<Vec<CorsRule>, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    client.put_bucket_cors(&bucket_name, &rules).await
+}
+
+#[tauri::command]
+pub async fn delete_bucket_cors(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+) -> Result<(), R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    client.delete_bucket_cors(&bucket_name).await
+}
+
+#[tauri::command]
+pub async fn validate_cors_rules(rules: Vec<CorsRule>) -> Result<Vec<CorsRule>, R2Error> {
+    cors::validate_cors_rules(&rules)
+}
+
+// Reads a rule set from a JSON file for review; nothing is applied to the bucket
+#[tauri::command]
+pub async fn import_cors_rules(file_path: String) -> Result<Vec<CorsRule>, R2Error> {
+    let json = tokio::fs::read_to_string(&file_path)
+        .await
+        .map_err(|e| R2Error::IoError(format!("{}: {}", t("file.read_failed"), e)))?;
+
+    cors::parse_cors_json(&json)
+}
+
+#[tauri::command]
+pub async fn export_cors_rules(file_path: String, rules: Vec<CorsRule>) -> Result<(), R2Error> {
+    let json = cors::cors_to_json(&rules)?;
+
+    tokio::fs::write(&file_path, json)
+        .await
+        .map_err(|e| R2Error::IoError(format!("{}: {}", t("file.write_failed"), e)))
+}
+
+#[tauri::command]
+pub async fn list_lifecycle_rules(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+) -> Result<Vec<LifecycleRule>, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    client.list_lifecycle_rules(&bucket_name).await
+}
+
+// The add, update and remove commands return the bucket's full rule set afterwards
+#[tauri::command]
+pub async fn add_lifecycle_rule(
+    pool: State<'_, ClientPool>,
     account_id: String,
     bucket_name: String,
-) -> Result<BucketInfo, String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let account = store
-        .get_account(&account_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("账户不存在")?;
-
-    let client = R2Client::new(
-        &account.account_id,
-        &account.access_key_id,
-        &account.secret_access_key,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+    rule: LifecycleRule,
+) -> Result<Vec<LifecycleRule>, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    client.add_lifecycle_rule(&bucket_name, &rule).await
+}
+
+#[tauri::command]
+pub async fn update_lifecycle_rule(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    rule_id: String,
+    rule: LifecycleRule,
+) -> Result<Vec<LifecycleRule>, R2Error> {
+    let client = pool.get(&account_id).await?;
 
     client
-        .get_bucket_info(&bucket_name)
+        .update_lifecycle_rule(&bucket_name, &rule_id, &rule)
         .await
-        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_lifecycle_rule(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    rule_id: String,
+) -> Result<Vec<LifecycleRule>, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    client.remove_lifecycle_rule(&bucket_name, &rule_id).await
+}
+
+#[tauri::command]
+pub async fn preview_lifecycle_rule(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    rule: LifecycleRule,
+) -> Result<LifecyclePreview, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    client.preview_lifecycle_rule(&bucket_name, &rule).await
 }
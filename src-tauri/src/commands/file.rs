@@ -1,104 +1,96 @@
-use crate::r2::client::R2Client;
-use crate::r2::types::ObjectInfo;
-use crate::storage::config::ConfigStore;
+use crate::r2::client::DELETE_BATCH_SIZE;
+use crate::r2::pool::ClientPool;
+use crate::r2::prefix::{PrefixDeleteResult, PrefixMoveResult};
+use crate::r2::types::{
+    DeleteResult, KeyError, MetadataReplacement, ObjectMetadata, ObjectPage, R2Error,
+};
+use crate::transfer::progress::BatchTracker;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
 pub async fn list_objects(
+    pool: State<'_, ClientPool>,
     account_id: String,
     bucket_name: String,
     prefix: Option<String>,
-) -> Result<Vec<ObjectInfo>, String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let account = store
-        .get_account(&account_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("账户不存在")?;
-
-    let client = R2Client::new(
-        &account.account_id,
-        &account.access_key_id,
-        &account.secret_access_key,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+    continuation_token: Option<String>,
+    max_keys: Option<i32>,
+) -> Result<ObjectPage, R2Error> {
+    let client = pool.get(&account_id).await?;
 
     client
-        .list_objects(&bucket_name, prefix.as_deref())
+        .list_objects(
+            &bucket_name,
+            prefix.as_deref(),
+            continuation_token.as_deref(),
+            max_keys,
+        )
         .await
-        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn delete_object(
+    pool: State<'_, ClientPool>,
     account_id: String,
     bucket_name: String,
     key: String,
-) -> Result<(), String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let account = store
-        .get_account(&account_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("账户不存在")?;
-
-    let client = R2Client::new(
-        &account.account_id,
-        &account.access_key_id,
-        &account.secret_access_key,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+) -> Result<(), R2Error> {
+    let client = pool.get(&account_id).await?;
 
-    client
-        .delete_object(&bucket_name, &key)
-        .await
-        .map_err(|e| e.to_string())
+    client.delete_object(&bucket_name, &key).await
 }
 
 #[tauri::command]
 pub async fn delete_objects(
+    pool: State<'_, ClientPool>,
     account_id: String,
     bucket_name: String,
     keys: Vec<String>,
-) -> Result<(), String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let account = store
-        .get_account(&account_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("账户不存在")?;
-
-    let client = R2Client::new(
-        &account.account_id,
-        &account.access_key_id,
-        &account.secret_access_key,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+) -> Result<DeleteResult, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    // A batch that fails outright counts against each of its keys, so the frontend can retry
+    // exactly the keys that are left
+    let mut result = DeleteResult::default();
+    for batch in keys.chunks(DELETE_BATCH_SIZE) {
+        match client.delete_objects(&bucket_name, batch).await {
+            Ok(batch_result) => result.merge(batch_result),
+            Err(error) => result.failed.extend(batch.iter().map(|key| KeyError {
+                key: key.clone(),
+                error: error.clone(),
+            })),
+        }
+    }
+
+    Ok(result)
+}
 
-    client
-        .delete_objects(&bucket_name, &keys)
-        .await
-        .map_err(|e| e.to_string())
+// Progress is reported on `batch-progress` under the caller's `operation_id`
+#[tauri::command]
+pub async fn delete_prefix(
+    app: AppHandle,
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    prefix: String,
+    operation_id: String,
+) -> Result<PrefixDeleteResult, R2Error> {
+    let client = pool.get(&account_id).await?;
+    let tracker = BatchTracker::new(app, operation_id);
+
+    let result = client.delete_prefix(&bucket_name, &prefix, &tracker).await;
+    tracker.finish();
+    result
 }
 
 #[tauri::command]
 pub async fn create_folder(
+    pool: State<'_, ClientPool>,
     account_id: String,
     bucket_name: String,
     path: String,
-) -> Result<(), String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let account = store
-        .get_account(&account_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("账户不存在")?;
-
-    let client = R2Client::new(
-        &account.account_id,
-        &account.access_key_id,
-        &account.secret_access_key,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+) -> Result<(), R2Error> {
+    let client = pool.get(&account_id).await?;
 
     // Create folder by uploading an empty object with trailing slash
     let folder_key = if path.ends_with('/') {
@@ -107,35 +99,150 @@ pub async fn create_folder(
         format!("{}/", path)
     };
 
-    client
-        .put_object(&bucket_name, &folder_key, vec![])
-        .await
-        .map_err(|e| e.to_string())
+    client.put_object(&bucket_name, &folder_key, vec![]).await
 }
 
 #[tauri::command]
 pub async fn get_presigned_url(
+    pool: State<'_, ClientPool>,
     account_id: String,
     bucket_name: String,
     key: String,
     expires_in: u64,
-) -> Result<String, String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let account = store
-        .get_account(&account_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("账户不存在")?;
-
-    let client = R2Client::new(
-        &account.account_id,
-        &account.access_key_id,
-        &account.secret_access_key,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+) -> Result<String, R2Error> {
+    let client = pool.get(&account_id).await?;
 
     client
         .get_presigned_url(&bucket_name, &key, expires_in)
         .await
-        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn copy_object(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    source_key: String,
+    dest_bucket: Option<String>,
+    dest_key: String,
+    replace_metadata: Option<MetadataReplacement>,
+    overwrite: Option<bool>,
+) -> Result<(), R2Error> {
+    let client = pool.get(&account_id).await?;
+    let dest_bucket = dest_bucket.unwrap_or_else(|| bucket_name.clone());
+
+    if !overwrite.unwrap_or(false) {
+        client.ensure_absent(&dest_bucket, &dest_key).await?;
+    }
+
+    client
+        .copy_object(
+            &bucket_name,
+            &source_key,
+            &dest_bucket,
+            &dest_key,
+            replace_metadata.as_ref(),
+        )
+        .await
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn move_object(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    source_key: String,
+    dest_bucket: Option<String>,
+    dest_key: String,
+    replace_metadata: Option<MetadataReplacement>,
+    overwrite: Option<bool>,
+) -> Result<(), R2Error> {
+    let client = pool.get(&account_id).await?;
+    let dest_bucket = dest_bucket.unwrap_or_else(|| bucket_name.clone());
+
+    if !overwrite.unwrap_or(false) && (dest_bucket != bucket_name || dest_key != source_key) {
+        client.ensure_absent(&dest_bucket, &dest_key).await?;
+    }
+
+    client
+        .move_object(
+            &bucket_name,
+            &source_key,
+            &dest_bucket,
+            &dest_key,
+            replace_metadata.as_ref(),
+        )
+        .await
+}
+
+#[tauri::command]
+pub async fn rename_object(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    key: String,
+    new_name: String,
+) -> Result<String, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    client.rename_object(&bucket_name, &key, &new_name).await
+}
+
+// Progress is reported on `batch-progress` under the caller's `operation_id`
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn move_prefix(
+    app: AppHandle,
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    source_prefix: String,
+    dest_bucket: Option<String>,
+    dest_prefix: String,
+    operation_id: String,
+) -> Result<PrefixMoveResult, R2Error> {
+    let client = pool.get(&account_id).await?;
+    let dest_bucket = dest_bucket.unwrap_or_else(|| bucket_name.clone());
+    let tracker = BatchTracker::new(app, operation_id);
+
+    let result = client
+        .move_prefix(
+            &bucket_name,
+            &source_prefix,
+            &dest_bucket,
+            &dest_prefix,
+            &tracker,
+        )
+        .await;
+    tracker.finish();
+    result
+}
+
+#[tauri::command]
+pub async fn head_object(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    key: String,
+) -> Result<ObjectMetadata, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    client.head_object(&bucket_name, &key).await
+}
+
+#[tauri::command]
+pub async fn update_object_metadata(
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    key: String,
+    replacement: MetadataReplacement,
+) -> Result<ObjectMetadata, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    client
+        .update_object_metadata(&bucket_name, &key, &replacement)
+        .await
 }
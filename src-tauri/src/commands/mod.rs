@@ -1,4 +1,6 @@
 pub mod account;
 pub mod bucket;
 pub mod file;
+pub mod settings;
 pub mod transfer;
+pub mod vault;
@@ -0,0 +1,50 @@
+use crate::i18n::{self, Locale};
+use crate::r2::headers::validate_rules;
+use crate::r2::pool::ClientPool;
+use crate::r2::types::R2Error;
+use crate::storage::config::ConfigStore;
+use crate::storage::settings::AppSettings;
+use crate::storage::vault;
+use crate::transfer::manager::TransferManager;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_settings() -> Result<AppSettings, R2Error> {
+    let store = ConfigStore::new()?;
+    Ok(store.get_settings()?)
+}
+
+#[derive(Debug, Serialize)]
+pub struct MessageCatalog {
+    pub locale: Locale,
+    pub messages: HashMap<&'static str, &'static str>,
+}
+
+#[tauri::command]
+pub async fn get_messages() -> Result<MessageCatalog, R2Error> {
+    Ok(MessageCatalog {
+        locale: i18n::current(),
+        messages: i18n::messages(),
+    })
+}
+
+#[tauri::command]
+pub async fn save_settings(
+    manager: State<'_, TransferManager>,
+    pool: State<'_, ClientPool>,
+    settings: AppSettings,
+) -> Result<(), R2Error> {
+    validate_rules(&settings.header_rules)?;
+
+    let store = ConfigStore::new()?;
+    store.save_settings(&settings)?;
+
+    i18n::set_locale(settings.locale);
+    // Clients carry the retry and timeout policy, so rebuild them on next use
+    pool.clear();
+    manager.set_max_concurrent(settings.transfer.max_concurrent_transfers);
+    vault::set_auto_lock(settings.security.vault_auto_lock_minutes);
+    Ok(())
+}
@@ -1,74 +1,207 @@
-use crate::r2::client::R2Client;
-use crate::storage::config::ConfigStore;
+use crate::i18n::t;
+use crate::r2::headers::UploadHeaders;
+use crate::r2::pool::ClientPool;
+use crate::r2::types::R2Error;
+use crate::storage::journal::{JournalEntry, TransferJournal, TransferKind};
+use crate::transfer::folder::{plan_folder_upload, plan_prefix_download, FolderUploadOptions};
+use crate::transfer::manager::{TransferManager, TransferSnapshot};
+use crate::transfer::runner::{
+    discard_transfer, new_entry, run_transfer, spawn_transfers, QueuedBatch,
+};
 use std::path::Path;
-use tokio::fs::File;
-use tokio::io::AsyncReadExt;
+use tauri::{AppHandle, Manager, State};
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn upload_file(
+    app: AppHandle,
+    transfer_id: String,
     account_id: String,
     bucket_name: String,
     key: String,
     file_path: String,
-) -> Result<(), String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let account = store
-        .get_account(&account_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("账户不存在")?;
-
-    let client = R2Client::new(
-        &account.account_id,
-        &account.access_key_id,
-        &account.secret_access_key,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
-
-    // Read file content
-    let path = Path::new(&file_path);
-    let mut file = File::open(path)
-        .await
-        .map_err(|e| format!("无法打开文件: {}", e))?;
-
-    let mut contents = Vec::new();
-    file.read_to_end(&mut contents)
-        .await
-        .map_err(|e| format!("无法读取文件: {}", e))?;
-
-    client
-        .put_object(&bucket_name, &key, contents)
-        .await
-        .map_err(|e| e.to_string())
+    priority: Option<i32>,
+    headers: Option<UploadHeaders>,
+) -> Result<(), R2Error> {
+    let mut entry = new_entry(
+        transfer_id,
+        TransferKind::Upload,
+        account_id,
+        bucket_name,
+        key,
+        file_path,
+    )?;
+    entry.headers = headers.unwrap_or_default();
+    app.state::<TransferJournal>().insert(entry.clone())?;
+
+    run_transfer(&app, entry, priority).await
 }
 
 #[tauri::command]
 pub async fn download_file(
+    app: AppHandle,
+    transfer_id: String,
     account_id: String,
     bucket_name: String,
     key: String,
     save_path: String,
-) -> Result<(), String> {
-    let store = ConfigStore::new().map_err(|e| e.to_string())?;
-    let account = store
-        .get_account(&account_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("账户不存在")?;
-
-    let client = R2Client::new(
-        &account.account_id,
-        &account.access_key_id,
-        &account.secret_access_key,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let data = client
-        .get_object(&bucket_name, &key)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    tokio::fs::write(&save_path, data)
-        .await
-        .map_err(|e| format!("无法保存文件: {}", e))
+    priority: Option<i32>,
+) -> Result<(), R2Error> {
+    let entry = new_entry(
+        transfer_id,
+        TransferKind::Download,
+        account_id,
+        bucket_name,
+        key,
+        save_path,
+    )?;
+    app.state::<TransferJournal>().insert(entry.clone())?;
+
+    run_transfer(&app, entry, priority).await
+}
+
+#[tauri::command]
+pub async fn upload_folder(
+    app: AppHandle,
+    account_id: String,
+    bucket_name: String,
+    prefix: Option<String>,
+    folder_path: String,
+    options: Option<FolderUploadOptions>,
+    headers: Option<UploadHeaders>,
+) -> Result<QueuedBatch, R2Error> {
+    let headers = headers.unwrap_or_default();
+    let planned = plan_folder_upload(
+        Path::new(&folder_path),
+        prefix.as_deref().unwrap_or_default(),
+        &options.unwrap_or_default(),
+    )?;
+
+    let entries = planned
+        .into_iter()
+        .map(|item| {
+            let mut entry = new_entry(
+                uuid::Uuid::new_v4().to_string(),
+                TransferKind::Upload,
+                account_id.clone(),
+                bucket_name.clone(),
+                item.key,
+                item.local_path,
+            )?;
+            entry.headers = headers.clone();
+            Ok::<_, R2Error>(entry)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    spawn_transfers(&app, entries)
+}
+
+#[tauri::command]
+pub async fn download_folder(
+    app: AppHandle,
+    pool: State<'_, ClientPool>,
+    account_id: String,
+    bucket_name: String,
+    prefix: String,
+    save_dir: String,
+) -> Result<QueuedBatch, R2Error> {
+    let client = pool.get(&account_id).await?;
+
+    // Without a trailing slash "photos" would also match "photos-old/..."
+    let prefix = match prefix.trim_start_matches('/') {
+        "" => String::new(),
+        p if p.ends_with('/') => p.to_string(),
+        p => format!("{}/", p),
+    };
+    let objects = client.list_objects_recursive(&bucket_name, &prefix).await?;
+
+    let planned = plan_prefix_download(&objects, &prefix, Path::new(&save_dir))?;
+
+    let mut entries = Vec::with_capacity(planned.len());
+    for item in planned {
+        if let Some(parent) = Path::new(&item.local_path).parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| R2Error::IoError(format!("{}: {}", t("file.create_dir_failed"), e)))?;
+        }
+
+        let mut entry = new_entry(
+            uuid::Uuid::new_v4().to_string(),
+            TransferKind::Download,
+            account_id.clone(),
+            bucket_name.clone(),
+            item.key,
+            item.local_path,
+        )?;
+        entry.total_bytes = item.size;
+        entries.push(entry);
+    }
+
+    spawn_transfers(&app, entries)
+}
+
+#[tauri::command]
+pub async fn cancel_transfer(
+    app: AppHandle,
+    manager: State<'_, TransferManager>,
+    journal: State<'_, TransferJournal>,
+    transfer_id: String,
+) -> Result<(), R2Error> {
+    if manager.is_active(&transfer_id) {
+        return manager.cancel(&transfer_id);
+    }
+
+    let entry = journal.get(&transfer_id).ok_or(R2Error::TransferNotFound)?;
+    discard_transfer(&app, &entry).await
+}
+
+#[tauri::command]
+pub async fn pause_transfer(
+    manager: State<'_, TransferManager>,
+    transfer_id: String,
+) -> Result<(), R2Error> {
+    manager.pause(&transfer_id)
+}
+
+// Resumes a paused transfer, or restarts one left in the journal by a previous session
+#[tauri::command]
+pub async fn resume_transfer(
+    app: AppHandle,
+    manager: State<'_, TransferManager>,
+    journal: State<'_, TransferJournal>,
+    transfer_id: String,
+) -> Result<(), R2Error> {
+    if manager.is_active(&transfer_id) {
+        return manager.resume(&transfer_id);
+    }
+
+    let entry = journal.get(&transfer_id).ok_or(R2Error::TransferNotFound)?;
+    run_transfer(&app, entry, None).await
+}
+
+#[tauri::command]
+pub async fn list_resumable_transfers(
+    manager: State<'_, TransferManager>,
+    journal: State<'_, TransferJournal>,
+) -> Result<Vec<JournalEntry>, R2Error> {
+    Ok(journal
+        .entries()
+        .into_iter()
+        .filter(|e| !manager.is_active(&e.id))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn set_transfer_priority(
+    manager: State<'_, TransferManager>,
+    transfer_id: String,
+    priority: i32,
+) -> Result<(), R2Error> {
+    manager.set_priority(&transfer_id, priority)
+}
+
+#[tauri::command]
+pub async fn list_transfers(
+    manager: State<'_, TransferManager>,
+) -> Result<Vec<TransferSnapshot>, R2Error> {
+    Ok(manager.snapshot())
 }
@@ -0,0 +1,34 @@
+use crate::r2::pool::ClientPool;
+use crate::r2::types::R2Error;
+use crate::storage::vault::{self, VaultStatus};
+use tauri::State;
+
+#[tauri::command]
+pub async fn vault_status() -> Result<VaultStatus, R2Error> {
+    Ok(vault::status()?)
+}
+
+// Key derivation is deliberately slow, so keep it off the async runtime
+#[tauri::command]
+pub async fn create_vault(password: String) -> Result<(), R2Error> {
+    tauri::async_runtime::spawn_blocking(move || vault::create(&password))
+        .await
+        .map_err(|e| R2Error::Unknown(e.to_string()))??;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unlock_vault(password: String) -> Result<(), R2Error> {
+    tauri::async_runtime::spawn_blocking(move || vault::unlock(&password))
+        .await
+        .map_err(|e| R2Error::Unknown(e.to_string()))??;
+    Ok(())
+}
+
+// Cached clients hold decrypted secrets, so they go when the vault locks
+#[tauri::command]
+pub async fn lock_vault(pool: State<'_, ClientPool>) -> Result<(), R2Error> {
+    vault::lock();
+    pool.clear();
+    Ok(())
+}
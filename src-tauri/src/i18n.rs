@@ -0,0 +1,347 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// Backend message catalog. Keys are stable and shared with the frontend; error labels live
+// under `error.<code>`, matching the `code` field commands reject with.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Locale {
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+impl Locale {
+    // Accepts BCP 47 tags and POSIX locale names alike ("en-GB", "zh_CN.UTF-8")
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_', '.', '@']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "" | "c" | "posix" => None,
+            _ => Some(Locale::EnUs),
+        }
+    }
+
+    // POSIX variables win when set, then the platform's preferred languages, which is all
+    // a GUI app gets on Windows or when launched from Finder. Falls back to Chinese, the
+    // language the app shipped with.
+    pub fn system() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .chain(sys_locale::get_locales())
+            .find_map(|value| Self::from_tag(&value))
+            .unwrap_or(Locale::ZhCn)
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::ZhCn => ZH_CN,
+            Locale::EnUs => EN_US,
+        }
+    }
+}
+
+static LOCALE: AtomicU8 = AtomicU8::new(0);
+
+// None follows the system locale
+pub fn set_locale(configured: Option<Locale>) {
+    let locale = configured.unwrap_or_else(Locale::system);
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn current() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::EnUs,
+        _ => Locale::ZhCn,
+    }
+}
+
+// Looks a key up in the active locale, then in zh-CN; an unknown key comes back as is
+pub fn t(key: &'static str) -> &'static str {
+    let lookup = |catalog: &'static [(&'static str, &'static str)]| {
+        catalog.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    };
+    lookup(current().catalog())
+        .or_else(|| lookup(ZH_CN))
+        .unwrap_or(key)
+}
+
+// Fills each `{}` in the message with the next argument
+pub fn tf(key: &'static str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut parts = t(key).split("{}");
+    let mut message = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            message.push_str(&arg.to_string());
+        }
+        message.push_str(part);
+    }
+    message
+}
+
+// The active catalog, for the frontend to render error codes and its own text with
+pub fn messages() -> HashMap<&'static str, &'static str> {
+    let mut messages: HashMap<_, _> = ZH_CN.iter().copied().collect();
+    messages.extend(current().catalog().iter().copied());
+    messages
+}
+
+const ZH_CN: &[(&str, &str)] = &[
+    ("error.sdk_error", "AWS SDK 错误"),
+    ("error.credentials_error", "凭证错误"),
+    ("error.bucket_not_found", "存储桶不存在"),
+    ("error.object_not_found", "对象不存在"),
+    ("error.network_error", "网络错误"),
+    ("error.precondition_failed", "对象已被修改"),
+    ("error.io_error", "文件读写错误"),
+    ("error.integrity_error", "完整性校验失败"),
+    ("error.invalid_input", "参数无效"),
+    ("error.config_error", "配置错误"),
+    ("error.vault_locked", "密码库已锁定，请先解锁"),
+    ("error.transfer_not_found", "传输不存在"),
+    ("error.cancelled", "传输已取消"),
+    ("error.unknown", "未知错误"),
+    ("config.io", "IO 错误"),
+    ("config.serialization", "序列化错误"),
+    ("config.dir", "配置目录错误"),
+    ("config.secret", "密钥存储错误"),
+    ("config.vault_password", "主密码错误"),
+    ("account.not_found", "账户不存在"),
+    ("vault.exists", "密码库已存在"),
+    ("vault.not_created", "密码库尚未创建"),
+    ("vault.empty_password", "主密码不能为空"),
+    ("vault.encrypt_failed", "加密失败"),
+    ("vault.decrypt_failed", "解密失败"),
+    ("vault.bad_kdf", "密码库的密钥派生参数无效"),
+    ("file.open_failed", "无法打开文件"),
+    ("file.read_failed", "无法读取文件"),
+    ("file.read_dir_failed", "无法读取文件夹"),
+    ("file.write_failed", "无法写入文件"),
+    ("file.create_dir_failed", "无法创建文件夹"),
+    ("file.not_a_file", "不是文件: {}"),
+    ("file.not_a_dir", "不是文件夹: {}"),
+    ("file.invalid_pattern", "无效的匹配模式 {}: {}"),
+    ("upload.missing_upload_id", "响应中缺少 UploadId"),
+    ("upload.part_missing_etag", "分片 {} 缺少 ETag"),
+    ("download.size_mismatch", "期望 {} 字节, 实际收到 {} 字节"),
+    ("download.etag_mismatch", "ETag 不匹配: 期望 {}, 实际 {}"),
+    ("transfer.already_running", "该传输已在进行中"),
+    ("copy.target_exists", "目标已存在: {}"),
+    ("copy.invalid_name", "名称无效: {}"),
+    ("copy.same_key", "不能将对象复制到自身, 除非替换其元数据"),
+    ("prefix.empty", "文件夹路径不能为空"),
+    ("prefix.into_itself", "不能把文件夹移动到它自身之内"),
+    ("delete.too_many_keys", "单次最多删除 {} 个对象"),
+    ("bucket.not_emptied", "存储桶未能清空, {} 个对象删除失败"),
+    ("bucket.name_empty", "存储桶名称不能为空"),
+    ("bucket.name_uppercase", "存储桶名称不能包含大写字母: {}"),
+    (
+        "bucket.name_invalid_char",
+        "存储桶名称只能包含小写字母、数字和连字符 (-), 不能包含: {}",
+    ),
+    (
+        "bucket.name_length",
+        "存储桶名称长度必须在 3 到 63 个字符之间 (当前 {})",
+    ),
+    (
+        "bucket.name_edge_hyphen",
+        "存储桶名称必须以字母或数字开头和结尾",
+    ),
+    (
+        "bucket.jurisdiction_custom_endpoint",
+        "使用自定义端点的账户不能指定管辖区",
+    ),
+    ("cors.too_many_rules", "CORS 规则最多 {} 条"),
+    ("cors.no_origins", "规则 {}: 至少需要一个允许的来源"),
+    ("cors.no_methods", "规则 {}: 至少需要一个允许的方法"),
+    ("cors.invalid_method", "规则 {}: 不支持的方法 {}"),
+    ("cors.invalid_origin", "规则 {}: 来源 {} 最多只能包含一个 *"),
+    (
+        "cors.invalid_header",
+        "规则 {}: 请求头 {} 最多只能包含一个 *",
+    ),
+    (
+        "cors.invalid_expose_header",
+        "规则 {}: 暴露的响应头 {} 不能包含 *",
+    ),
+    ("cors.invalid_max_age", "规则 {}: 缓存时间不能为负数 ({})"),
+    ("cors.invalid_json", "无法解析 CORS 规则 JSON: {}"),
+    ("lifecycle.too_many_rules", "生命周期规则最多 {} 条"),
+    (
+        "lifecycle.invalid_id",
+        "规则 ID 不能为空, 且不能超过 {} 个字符",
+    ),
+    ("lifecycle.duplicate_id", "规则 ID 已存在: {}"),
+    ("lifecycle.rule_not_found", "规则不存在: {}"),
+    (
+        "lifecycle.no_action",
+        "规则 {}: 请至少设置过期或清理未完成分片上传中的一项",
+    ),
+    (
+        "lifecycle.conflicting_expiration",
+        "规则 {}: 过期天数和过期日期只能设置一个",
+    ),
+    (
+        "lifecycle.invalid_days",
+        "规则 {}: 天数必须大于 0 (当前 {})",
+    ),
+    (
+        "lifecycle.invalid_date",
+        "日期格式无效, 应为 YYYY-MM-DD: {}",
+    ),
+];
+
+const EN_US: &[(&str, &str)] = &[
+    ("error.sdk_error", "AWS SDK error"),
+    ("error.credentials_error", "Credentials error"),
+    ("error.bucket_not_found", "Bucket not found"),
+    ("error.object_not_found", "Object not found"),
+    ("error.network_error", "Network error"),
+    ("error.precondition_failed", "The object has changed"),
+    ("error.io_error", "File I/O error"),
+    ("error.integrity_error", "Integrity check failed"),
+    ("error.invalid_input", "Invalid input"),
+    ("error.config_error", "Configuration error"),
+    ("error.vault_locked", "The vault is locked; unlock it first"),
+    ("error.transfer_not_found", "Transfer not found"),
+    ("error.cancelled", "Transfer cancelled"),
+    ("error.unknown", "Unknown error"),
+    ("config.io", "I/O error"),
+    ("config.serialization", "Serialization error"),
+    ("config.dir", "Configuration directory unavailable"),
+    ("config.secret", "Secret storage error"),
+    ("config.vault_password", "Wrong master password"),
+    ("account.not_found", "Account not found"),
+    ("vault.exists", "The vault already exists"),
+    ("vault.not_created", "The vault has not been created yet"),
+    (
+        "vault.empty_password",
+        "The master password cannot be empty",
+    ),
+    ("vault.encrypt_failed", "Encryption failed"),
+    ("vault.decrypt_failed", "Decryption failed"),
+    (
+        "vault.bad_kdf",
+        "The vault's key derivation parameters are invalid",
+    ),
+    ("file.open_failed", "Cannot open file"),
+    ("file.read_failed", "Cannot read file"),
+    ("file.read_dir_failed", "Cannot read folder"),
+    ("file.write_failed", "Cannot write file"),
+    ("file.create_dir_failed", "Cannot create folder"),
+    ("file.not_a_file", "Not a file: {}"),
+    ("file.not_a_dir", "Not a folder: {}"),
+    ("file.invalid_pattern", "Invalid pattern {}: {}"),
+    (
+        "upload.missing_upload_id",
+        "Response is missing the UploadId",
+    ),
+    ("upload.part_missing_etag", "Part {} has no ETag"),
+    ("download.size_mismatch", "Expected {} bytes, received {}"),
+    (
+        "download.etag_mismatch",
+        "ETag mismatch: expected {}, got {}",
+    ),
+    (
+        "transfer.already_running",
+        "This transfer is already running",
+    ),
+    ("copy.target_exists", "Target already exists: {}"),
+    ("copy.invalid_name", "Invalid name: {}"),
+    (
+        "copy.same_key",
+        "An object can only be copied onto itself when its metadata is replaced",
+    ),
+    ("prefix.empty", "The folder path cannot be empty"),
+    ("prefix.into_itself", "A folder cannot be moved into itself"),
+    (
+        "delete.too_many_keys",
+        "At most {} objects can be deleted per request",
+    ),
+    (
+        "bucket.not_emptied",
+        "The bucket could not be emptied; {} objects failed to delete",
+    ),
+    ("bucket.name_empty", "The bucket name cannot be empty"),
+    (
+        "bucket.name_uppercase",
+        "Bucket names cannot contain uppercase letters: {}",
+    ),
+    (
+        "bucket.name_invalid_char",
+        "Bucket names may only contain lowercase letters, digits and hyphens (-), not: {}",
+    ),
+    (
+        "bucket.name_length",
+        "Bucket names must be 3 to 63 characters long (got {})",
+    ),
+    (
+        "bucket.name_edge_hyphen",
+        "Bucket names must start and end with a letter or digit",
+    ),
+    (
+        "bucket.jurisdiction_custom_endpoint",
+        "A jurisdiction cannot be chosen for an account with a custom endpoint",
+    ),
+    ("cors.too_many_rules", "At most {} CORS rules are allowed"),
+    (
+        "cors.no_origins",
+        "Rule {}: at least one allowed origin is required",
+    ),
+    (
+        "cors.no_methods",
+        "Rule {}: at least one allowed method is required",
+    ),
+    ("cors.invalid_method", "Rule {}: unsupported method {}"),
+    (
+        "cors.invalid_origin",
+        "Rule {}: origin {} may contain at most one *",
+    ),
+    (
+        "cors.invalid_header",
+        "Rule {}: header {} may contain at most one *",
+    ),
+    (
+        "cors.invalid_expose_header",
+        "Rule {}: exposed header {} cannot contain *",
+    ),
+    (
+        "cors.invalid_max_age",
+        "Rule {}: max age cannot be negative ({})",
+    ),
+    ("cors.invalid_json", "Cannot parse the CORS rules JSON: {}"),
+    (
+        "lifecycle.too_many_rules",
+        "At most {} lifecycle rules are allowed",
+    ),
+    (
+        "lifecycle.invalid_id",
+        "The rule ID must be 1 to {} characters long",
+    ),
+    (
+        "lifecycle.duplicate_id",
+        "A rule with this ID already exists: {}",
+    ),
+    ("lifecycle.rule_not_found", "Rule not found: {}"),
+    (
+        "lifecycle.no_action",
+        "Rule {}: set an expiration or an incomplete multipart upload cleanup",
+    ),
+    (
+        "lifecycle.conflicting_expiration",
+        "Rule {}: set either expiration days or an expiration date, not both",
+    ),
+    (
+        "lifecycle.invalid_days",
+        "Rule {}: days must be greater than 0 (got {})",
+    ),
+    (
+        "lifecycle.invalid_date",
+        "Invalid date, expected YYYY-MM-DD: {}",
+    ),
+];
@@ -1,34 +1,102 @@
 mod commands;
+mod i18n;
 mod r2;
 mod storage;
+mod transfer;
 
-use commands::{bucket, file, transfer};
+use commands::{bucket, file};
+use r2::pool::ClientPool;
+use std::time::Duration;
+use storage::config::ConfigStore;
+use storage::journal::TransferJournal;
+use storage::vault;
+use tauri::Manager;
+use transfer::manager::TransferManager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .setup(|app| {
+            let settings = ConfigStore::new()?.get_settings()?;
+            i18n::set_locale(settings.locale);
+            app.manage(TransferManager::new(
+                settings.transfer.max_concurrent_transfers,
+            ));
+            app.manage(TransferJournal::open()?);
+            app.manage(ClientPool::new());
+
+            // Lock an idle vault even when nothing touches it, and drop the clients
+            // built from its secrets however the vault got locked
+            vault::set_auto_lock(settings.security.vault_auto_lock_minutes);
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut ticker = tokio::time::interval(Duration::from_secs(30));
+                loop {
+                    ticker.tick().await;
+                    vault::expire_idle();
+                    handle.state::<ClientPool>().drop_locked();
+                }
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Bucket commands
             bucket::list_buckets,
             bucket::create_bucket,
             bucket::delete_bucket,
             bucket::get_bucket_info,
+            bucket::validate_bucket_name,
+            bucket::get_bucket_cors,
+            bucket::put_bucket_cors,
+            bucket::delete_bucket_cors,
+            bucket::validate_cors_rules,
+            bucket::import_cors_rules,
+            bucket::export_cors_rules,
+            bucket::list_lifecycle_rules,
+            bucket::add_lifecycle_rule,
+            bucket::update_lifecycle_rule,
+            bucket::remove_lifecycle_rule,
+            bucket::preview_lifecycle_rule,
             // File commands
             file::list_objects,
             file::delete_object,
             file::delete_objects,
             file::create_folder,
             file::get_presigned_url,
+            file::copy_object,
+            file::move_object,
+            file::rename_object,
+            file::move_prefix,
+            file::delete_prefix,
+            file::head_object,
+            file::update_object_metadata,
             // Transfer commands
-            transfer::upload_file,
-            transfer::download_file,
+            commands::transfer::upload_file,
+            commands::transfer::download_file,
+            commands::transfer::upload_folder,
+            commands::transfer::download_folder,
+            commands::transfer::cancel_transfer,
+            commands::transfer::pause_transfer,
+            commands::transfer::resume_transfer,
+            commands::transfer::list_resumable_transfers,
+            commands::transfer::set_transfer_priority,
+            commands::transfer::list_transfers,
             // Account commands
             commands::account::save_account,
             commands::account::get_accounts,
             commands::account::delete_account,
             commands::account::validate_credentials,
+            // Settings commands
+            commands::settings::get_settings,
+            commands::settings::save_settings,
+            commands::settings::get_messages,
+            // Vault commands
+            commands::vault::vault_status,
+            commands::vault::create_vault,
+            commands::vault::unlock_vault,
+            commands::vault::lock_vault,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,16 +1,64 @@
-use super::types::{BucketInfo, ObjectInfo, R2Error};
+use super::headers::{detect_content_type, UploadHeaders};
+use super::retry::NetworkConfig;
+use super::types::{
+    BucketInfo, DeleteResult, EndpointConfig, KeyError, LocationHint, ObjectInfo, ObjectMetadata,
+    ObjectPage, ObjectStream, PartInfo, R2Error, UploadedPart,
+};
+use crate::i18n::{t, tf};
 use aws_credential_types::Credentials;
 use aws_sdk_s3::{
     config::{Builder, Region},
-    primitives::ByteStream,
+    error::ProvideErrorMetadata,
+    primitives::{ByteStream, Length},
+    types::{
+        BucketLocationConstraint, CompletedMultipartUpload, CompletedPart,
+        CreateBucketConfiguration,
+    },
     Client,
 };
+use std::collections::HashSet;
+use std::path::Path;
 use std::time::Duration;
 
+// DeleteObjects accepts at most this many keys per request
+pub const DELETE_BATCH_SIZE: usize = 1000;
+
+// R2 bucket names: 3-63 lowercase letters, digits and hyphens, starting and ending with a
+// letter or digit. Checked locally because R2 answers every violation with InvalidBucketName.
+pub fn validate_bucket_name(name: &str) -> Result<(), R2Error> {
+    let invalid = |key| Err(R2Error::InvalidInput(t(key).to_string()));
+
+    if name.is_empty() {
+        return invalid("bucket.name_empty");
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '-'))
+    {
+        let key = if c.is_ascii_uppercase() {
+            "bucket.name_uppercase"
+        } else {
+            "bucket.name_invalid_char"
+        };
+        return Err(R2Error::InvalidInput(tf(key, &[&c])));
+    }
+    if !(3..=63).contains(&name.len()) {
+        return Err(R2Error::InvalidInput(tf(
+            "bucket.name_length",
+            &[&name.len()],
+        )));
+    }
+    if name.starts_with('-') || name.ends_with('-') {
+        return invalid("bucket.name_edge_hyphen");
+    }
+    Ok(())
+}
+
 pub struct R2Client {
-    client: Client,
+    pub(super) client: Client,
     #[allow(dead_code)]
     account_id: String,
+    network: NetworkConfig,
 }
 
 impl R2Client {
@@ -18,22 +66,21 @@ impl R2Client {
         account_id: &str,
         access_key_id: &str,
         secret_access_key: &str,
+        endpoint: &EndpointConfig,
+        network: &NetworkConfig,
     ) -> Result<Self, R2Error> {
-        let endpoint = format!("https://{}.r2.cloudflarestorage.com", account_id);
+        let endpoint_url = endpoint.endpoint_url(account_id);
 
-        let credentials = Credentials::new(
-            access_key_id,
-            secret_access_key,
-            None,
-            None,
-            "r2-explorer",
-        );
+        let credentials =
+            Credentials::new(access_key_id, secret_access_key, None, None, "r2-explorer");
 
         let config = Builder::new()
-            .endpoint_url(&endpoint)
+            .endpoint_url(&endpoint_url)
             .credentials_provider(credentials)
-            .region(Region::new("auto"))
-            .force_path_style(true)
+            .region(Region::new(endpoint.region()))
+            .force_path_style(endpoint.force_path_style)
+            .retry_config(network.retry_config())
+            .timeout_config(network.timeout_config())
             .build();
 
         let client = Client::from_conf(config);
@@ -41,16 +88,21 @@ impl R2Client {
         Ok(Self {
             client,
             account_id: account_id.to_string(),
+            network: *network,
         })
     }
 
+    pub fn network(&self) -> &NetworkConfig {
+        &self.network
+    }
+
     pub async fn list_buckets(&self) -> Result<Vec<BucketInfo>, R2Error> {
         let response = self
             .client
             .list_buckets()
             .send()
             .await
-            .map_err(|e| R2Error::SdkError(e.to_string()))?;
+            .map_err(R2Error::from_sdk)?;
 
         let buckets = response
             .buckets()
@@ -64,13 +116,26 @@ impl R2Client {
         Ok(buckets)
     }
 
-    pub async fn create_bucket(&self, bucket_name: &str) -> Result<(), R2Error> {
+    pub async fn create_bucket(
+        &self,
+        bucket_name: &str,
+        location_hint: Option<LocationHint>,
+    ) -> Result<(), R2Error> {
+        validate_bucket_name(bucket_name)?;
+
+        let configuration = location_hint.map(|hint| {
+            CreateBucketConfiguration::builder()
+                .location_constraint(BucketLocationConstraint::from(hint.as_str()))
+                .build()
+        });
+
         self.client
             .create_bucket()
             .bucket(bucket_name)
+            .set_create_bucket_configuration(configuration)
             .send()
             .await
-            .map_err(|e| R2Error::SdkError(e.to_string()))?;
+            .map_err(R2Error::from_sdk)?;
 
         Ok(())
     }
@@ -81,7 +146,7 @@ impl R2Client {
             .bucket(bucket_name)
             .send()
             .await
-            .map_err(|e| R2Error::SdkError(e.to_string()))?;
+            .map_err(R2Error::from_sdk)?;
 
         Ok(())
     }
@@ -93,7 +158,11 @@ impl R2Client {
             .bucket(bucket_name)
             .send()
             .await
-            .map_err(|e| R2Error::BucketNotFound(e.to_string()))?;
+            .map_err(|e| match R2Error::from_sdk(e) {
+                // HeadBucket only has a status code to report a missing bucket
+                R2Error::ObjectNotFound(detail) => R2Error::BucketNotFound(detail),
+                other => other,
+            })?;
 
         Ok(BucketInfo {
             name: bucket_name.to_string(),
@@ -101,23 +170,33 @@ impl R2Client {
         })
     }
 
+    // One page of a single folder level; pass the returned token back to get the next page
     pub async fn list_objects(
         &self,
         bucket_name: &str,
         prefix: Option<&str>,
-    ) -> Result<Vec<ObjectInfo>, R2Error> {
-        let mut request = self.client.list_objects_v2().bucket(bucket_name).delimiter("/");
+        continuation_token: Option<&str>,
+        max_keys: Option<i32>,
+    ) -> Result<ObjectPage, R2Error> {
+        let mut request = self
+            .client
+            .list_objects_v2()
+            .bucket(bucket_name)
+            .delimiter("/")
+            .set_continuation_token(continuation_token.map(|t| t.to_string()))
+            .set_max_keys(max_keys);
 
         if let Some(p) = prefix {
             if !p.is_empty() {
-                request = request.prefix(if p.ends_with('/') { p.to_string() } else { format!("{}/", p) });
+                request = request.prefix(if p.ends_with('/') {
+                    p.to_string()
+                } else {
+                    format!("{}/", p)
+                });
             }
         }
 
-        let response = request
-            .send()
-            .await
-            .map_err(|e| R2Error::SdkError(e.to_string()))?;
+        let response = request.send().await.map_err(R2Error::from_sdk)?;
 
         let mut objects = Vec::new();
 
@@ -166,6 +245,68 @@ impl R2Client {
             });
         }
 
+        let continuation_token = response
+            .next_continuation_token()
+            .filter(|_| response.is_truncated().unwrap_or(false))
+            .map(|t| t.to_string());
+
+        Ok(ObjectPage {
+            objects,
+            has_more: continuation_token.is_some(),
+            continuation_token,
+        })
+    }
+
+    // Every object under the prefix, at any depth, following continuation tokens
+    pub async fn list_objects_recursive(
+        &self,
+        bucket_name: &str,
+        prefix: &str,
+    ) -> Result<Vec<ObjectInfo>, R2Error> {
+        let mut objects = Vec::new();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let mut request = self.client.list_objects_v2().bucket(bucket_name);
+            if !prefix.is_empty() {
+                request = request.prefix(prefix);
+            }
+            if let Some(token) = continuation_token.take() {
+                request = request.continuation_token(token);
+            }
+
+            let response = request.send().await.map_err(R2Error::from_sdk)?;
+
+            for object in response.contents() {
+                let key = object.key().unwrap_or_default();
+                let name = key
+                    .trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(key)
+                    .to_string();
+
+                objects.push(ObjectInfo {
+                    key: key.to_string(),
+                    name,
+                    size: object.size().unwrap_or(0),
+                    last_modified: object
+                        .last_modified()
+                        .map(|d| d.to_string())
+                        .unwrap_or_default(),
+                    is_folder: key.ends_with('/'),
+                    etag: object.e_tag().map(|s| s.to_string()),
+                });
+            }
+
+            match response.next_continuation_token() {
+                Some(token) if response.is_truncated().unwrap_or(false) => {
+                    continuation_token = Some(token.to_string());
+                }
+                _ => break,
+            }
+        }
+
         Ok(objects)
     }
 
@@ -179,33 +320,257 @@ impl R2Client {
             .put_object()
             .bucket(bucket_name)
             .key(key)
+            .set_content_type(detect_content_type(key, &data))
             .body(ByteStream::from(data))
             .send()
             .await
-            .map_err(|e| R2Error::SdkError(e.to_string()))?;
+            .map_err(R2Error::from_sdk)?;
+
+        Ok(())
+    }
+
+    pub async fn put_object_from_path(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        path: &Path,
+        headers: &UploadHeaders,
+    ) -> Result<(), R2Error> {
+        let body = ByteStream::from_path(path)
+            .await
+            .map_err(|e| R2Error::IoError(e.to_string()))?;
+
+        self.client
+            .put_object()
+            .bucket(bucket_name)
+            .key(key)
+            .set_content_type(headers.content_type.clone())
+            .set_cache_control(headers.cache_control.clone())
+            .set_content_disposition(headers.content_disposition.clone())
+            .set_content_encoding(headers.content_encoding.clone())
+            .set_metadata(Some(headers.metadata.clone()))
+            .body(body)
+            .send()
+            .await
+            .map_err(R2Error::from_sdk)?;
 
         Ok(())
     }
 
-    pub async fn get_object(&self, bucket_name: &str, key: &str) -> Result<Vec<u8>, R2Error> {
+    pub async fn create_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        headers: &UploadHeaders,
+    ) -> Result<String, R2Error> {
         let response = self
             .client
-            .get_object()
+            .create_multipart_upload()
             .bucket(bucket_name)
             .key(key)
+            .set_content_type(headers.content_type.clone())
+            .set_cache_control(headers.cache_control.clone())
+            .set_content_disposition(headers.content_disposition.clone())
+            .set_content_encoding(headers.content_encoding.clone())
+            .set_metadata(Some(headers.metadata.clone()))
             .send()
             .await
-            .map_err(|e| R2Error::SdkError(e.to_string()))?;
+            .map_err(R2Error::from_sdk)?;
 
-        let data = response
-            .body
-            .collect()
+        response
+            .upload_id()
+            .map(|id| id.to_string())
+            .ok_or_else(|| R2Error::Unknown(t("upload.missing_upload_id").to_string()))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upload_part(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        path: &Path,
+        offset: u64,
+        length: u64,
+    ) -> Result<UploadedPart, R2Error> {
+        // Stream the part straight from disk instead of buffering it
+        let body = ByteStream::read_from()
+            .path(path)
+            .offset(offset)
+            .length(Length::Exact(length))
+            .build()
+            .await
+            .map_err(|e| R2Error::IoError(e.to_string()))?;
+
+        let response = self
+            .client
+            .upload_part()
+            .bucket(bucket_name)
+            .key(key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .content_length(length as i64)
+            .body(body)
+            .send()
             .await
-            .map_err(|e| R2Error::SdkError(e.to_string()))?
-            .into_bytes()
-            .to_vec();
+            .map_err(R2Error::from_sdk)?;
 
-        Ok(data)
+        let etag = response
+            .e_tag()
+            .ok_or_else(|| R2Error::Unknown(tf("upload.part_missing_etag", &[&part_number])))?;
+
+        Ok(UploadedPart {
+            part_number,
+            etag: etag.to_string(),
+        })
+    }
+
+    pub async fn complete_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        parts: &[UploadedPart],
+    ) -> Result<(), R2Error> {
+        let completed_parts: Vec<CompletedPart> = parts
+            .iter()
+            .map(|p| {
+                CompletedPart::builder()
+                    .part_number(p.part_number)
+                    .e_tag(&p.etag)
+                    .build()
+            })
+            .collect();
+
+        let upload = CompletedMultipartUpload::builder()
+            .set_parts(Some(completed_parts))
+            .build();
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(bucket_name)
+            .key(key)
+            .upload_id(upload_id)
+            .multipart_upload(upload)
+            .send()
+            .await
+            .map_err(R2Error::from_sdk)?;
+
+        Ok(())
+    }
+
+    // Returns None when the upload no longer exists (completed, aborted or expired)
+    pub async fn list_parts(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+    ) -> Result<Option<Vec<PartInfo>>, R2Error> {
+        let mut parts = Vec::new();
+        let mut marker: Option<String> = None;
+
+        loop {
+            let response = match self
+                .client
+                .list_parts()
+                .bucket(bucket_name)
+                .key(key)
+                .upload_id(upload_id)
+                .set_part_number_marker(marker.take())
+                .send()
+                .await
+            {
+                Ok(response) => response,
+                Err(e) if e.code() == Some("NoSuchUpload") => return Ok(None),
+                Err(e) => return Err(R2Error::from_sdk(e)),
+            };
+
+            for part in response.parts() {
+                parts.push(PartInfo {
+                    part_number: part.part_number().unwrap_or_default(),
+                    etag: part.e_tag().unwrap_or_default().to_string(),
+                    size: part.size().unwrap_or(0).max(0) as u64,
+                });
+            }
+
+            marker = response.next_part_number_marker().map(|m| m.to_string());
+            if response.is_truncated() != Some(true) || marker.is_none() {
+                break;
+            }
+        }
+
+        Ok(Some(parts))
+    }
+
+    pub async fn abort_multipart_upload(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+    ) -> Result<(), R2Error> {
+        self.client
+            .abort_multipart_upload()
+            .bucket(bucket_name)
+            .key(key)
+            .upload_id(upload_id)
+            .send()
+            .await
+            .map_err(R2Error::from_sdk)?;
+
+        Ok(())
+    }
+
+    pub async fn head_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+    ) -> Result<ObjectMetadata, R2Error> {
+        let response = self
+            .client
+            .head_object()
+            .bucket(bucket_name)
+            .key(key)
+            .send()
+            .await
+            .map_err(R2Error::from_sdk)?;
+
+        Ok(ObjectMetadata {
+            key: key.to_string(),
+            size: response.content_length().unwrap_or(0).max(0) as u64,
+            etag: response.e_tag().map(|s| s.to_string()),
+            last_modified: response.last_modified().map(|d| d.to_string()),
+            content_type: response.content_type().map(|s| s.to_string()),
+            cache_control: response.cache_control().map(|s| s.to_string()),
+            content_disposition: response.content_disposition().map(|s| s.to_string()),
+            content_encoding: response.content_encoding().map(|s| s.to_string()),
+            metadata: response.metadata().cloned().unwrap_or_default(),
+        })
+    }
+
+    pub async fn get_object_stream(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        range_start: Option<u64>,
+        if_match: Option<&str>,
+    ) -> Result<ObjectStream, R2Error> {
+        let response = self
+            .client
+            .get_object()
+            .bucket(bucket_name)
+            .key(key)
+            .set_range(range_start.map(|start| format!("bytes={}-", start)))
+            .set_if_match(if_match.map(|etag| etag.to_string()))
+            .send()
+            .await
+            .map_err(R2Error::from_sdk)?;
+
+        Ok(ObjectStream {
+            content_length: response.content_length().unwrap_or(0).max(0) as u64,
+            etag: response.e_tag().map(|s| s.to_string()),
+            body: response.body,
+        })
     }
 
     pub async fn delete_object(&self, bucket_name: &str, key: &str) -> Result<(), R2Error> {
@@ -215,37 +580,68 @@ impl R2Client {
             .key(key)
             .send()
             .await
-            .map_err(|e| R2Error::SdkError(e.to_string()))?;
+            .map_err(R2Error::from_sdk)?;
 
         Ok(())
     }
 
-    pub async fn delete_objects(&self, bucket_name: &str, keys: &[String]) -> Result<(), R2Error> {
+    // One DeleteObjects request, so at most DELETE_BATCH_SIZE keys. Keys R2 refused are
+    // listed in the result; a request that fails as a whole is an error.
+    pub async fn delete_objects(
+        &self,
+        bucket_name: &str,
+        keys: &[String],
+    ) -> Result<DeleteResult, R2Error> {
         use aws_sdk_s3::types::{Delete, ObjectIdentifier};
 
+        if keys.len() > DELETE_BATCH_SIZE {
+            return Err(R2Error::InvalidInput(tf(
+                "delete.too_many_keys",
+                &[&DELETE_BATCH_SIZE],
+            )));
+        }
+
         let objects: Vec<ObjectIdentifier> = keys
             .iter()
             .filter_map(|k| ObjectIdentifier::builder().key(k).build().ok())
             .collect();
 
         if objects.is_empty() {
-            return Ok(());
+            return Ok(DeleteResult::default());
         }
 
+        // Quiet mode lists only the failures; every other key was deleted
         let delete = Delete::builder()
             .set_objects(Some(objects))
+            .quiet(true)
             .build()
-            .map_err(|e| R2Error::SdkError(e.to_string()))?;
+            .map_err(|e| R2Error::InvalidInput(e.to_string()))?;
 
-        self.client
+        let response = self
+            .client
             .delete_objects()
             .bucket(bucket_name)
             .delete(delete)
             .send()
             .await
-            .map_err(|e| R2Error::SdkError(e.to_string()))?;
+            .map_err(R2Error::from_sdk)?;
 
-        Ok(())
+        let failed: Vec<KeyError> = response
+            .errors()
+            .iter()
+            .map(|e| KeyError {
+                key: e.key().unwrap_or_default().to_string(),
+                error: R2Error::from_key_error(e.code(), e.message()),
+            })
+            .collect();
+        let refused: HashSet<&str> = failed.iter().map(|f| f.key.as_str()).collect();
+        let deleted = keys
+            .iter()
+            .filter(|key| !refused.contains(key.as_str()))
+            .cloned()
+            .collect();
+
+        Ok(DeleteResult { deleted, failed })
     }
 
     pub async fn get_presigned_url(
@@ -257,7 +653,7 @@ impl R2Client {
         let presigning_config = aws_sdk_s3::presigning::PresigningConfig::builder()
             .expires_in(Duration::from_secs(expires_in))
             .build()
-            .map_err(|e| R2Error::SdkError(e.to_string()))?;
+            .map_err(|e| R2Error::InvalidInput(e.to_string()))?;
 
         let presigned = self
             .client
@@ -266,7 +662,7 @@ impl R2Client {
             .key(key)
             .presigned(presigning_config)
             .await
-            .map_err(|e| R2Error::SdkError(e.to_string()))?;
+            .map_err(R2Error::from_sdk)?;
 
         Ok(presigned.uri().to_string())
     }
@@ -0,0 +1,59 @@
+use super::types::{CheckpointSink, ProgressSink, R2Error};
+use tokio::sync::watch;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlSignal {
+    Run,
+    Pause,
+    Cancel,
+}
+
+pub struct TransferHooks<'a> {
+    pub progress: &'a dyn ProgressSink,
+    pub control: &'a TransferControl,
+    // Set for journaled transfers; their failures keep remote and local state for a later resume
+    pub checkpoint: Option<&'a dyn CheckpointSink>,
+}
+
+#[derive(Clone)]
+pub struct TransferControl {
+    receiver: watch::Receiver<ControlSignal>,
+}
+
+pub fn control_channel() -> (watch::Sender<ControlSignal>, TransferControl) {
+    let (sender, receiver) = watch::channel(ControlSignal::Run);
+    (sender, TransferControl { receiver })
+}
+
+impl TransferControl {
+    // Waits while the transfer is paused; fails once it has been cancelled
+    pub async fn checkpoint(&self) -> Result<(), R2Error> {
+        let mut receiver = self.receiver.clone();
+        let signal = receiver
+            .wait_for(|s| *s != ControlSignal::Pause)
+            .await
+            .map(|s| *s);
+
+        match signal {
+            Ok(ControlSignal::Cancel) => Err(R2Error::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    // Resolves when the transfer is cancelled, never if the sender has gone away
+    pub async fn cancelled(&self) {
+        self.wait_until(|s| s == ControlSignal::Cancel).await
+    }
+
+    // Resolves when the transfer is paused or cancelled
+    pub async fn interrupted(&self) {
+        self.wait_until(|s| s != ControlSignal::Run).await
+    }
+
+    async fn wait_until(&self, predicate: impl Fn(ControlSignal) -> bool) {
+        let mut receiver = self.receiver.clone();
+        if receiver.wait_for(|s| predicate(*s)).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
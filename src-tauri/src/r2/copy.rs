@@ -0,0 +1,314 @@
+use super::client::R2Client;
+use super::types::{MetadataReplacement, ObjectMetadata, R2Error, UploadedPart};
+use super::upload::{MAX_PARTS, MAX_PART_SIZE};
+use crate::i18n::{t, tf};
+use aws_sdk_s3::types::MetadataDirective;
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+const MIB: u64 = 1024 * 1024;
+
+// CopyObject takes at most 5 GiB; anything larger goes through UploadPartCopy
+pub const MAX_SINGLE_COPY_SIZE: u64 = MAX_PART_SIZE;
+const COPY_PART_SIZE: u64 = 512 * MIB;
+const MAX_CONCURRENT_COPY_PARTS: usize = 4;
+
+// x-amz-copy-source is a URL path, so everything but unreserved characters and `/` is escaped
+const COPY_SOURCE_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b'/');
+
+fn copy_source(bucket_name: &str, key: &str) -> String {
+    format!(
+        "{}/{}",
+        bucket_name,
+        utf8_percent_encode(key, COPY_SOURCE_ENCODE_SET)
+    )
+}
+
+fn replace_header(header: &mut Option<String>, replacement: &Option<String>) {
+    if let Some(value) = replacement {
+        let value = value.trim();
+        *header = (!value.is_empty()).then(|| value.to_string());
+    }
+}
+
+impl ObjectMetadata {
+    fn replaced(mut self, replacement: &MetadataReplacement) -> Self {
+        replace_header(&mut self.content_type, &replacement.content_type);
+        replace_header(&mut self.cache_control, &replacement.cache_control);
+        replace_header(
+            &mut self.content_disposition,
+            &replacement.content_disposition,
+        );
+        replace_header(&mut self.content_encoding, &replacement.content_encoding);
+        if let Some(metadata) = &replacement.metadata {
+            self.metadata = metadata.clone();
+        }
+        self
+    }
+}
+
+impl R2Client {
+    // Copies server-side, keeping the source's headers and metadata unless `replacement`
+    // overrides them
+    pub async fn copy_object(
+        &self,
+        source_bucket: &str,
+        source_key: &str,
+        dest_bucket: &str,
+        dest_key: &str,
+        replacement: Option<&MetadataReplacement>,
+    ) -> Result<(), R2Error> {
+        // R2 refuses a self-copy that changes nothing
+        if source_bucket == dest_bucket && source_key == dest_key && replacement.is_none() {
+            return Err(R2Error::InvalidInput(t("copy.same_key").to_string()));
+        }
+
+        let source = self.head_object(source_bucket, source_key).await?;
+
+        if source.size <= MAX_SINGLE_COPY_SIZE {
+            return self
+                .copy_single(
+                    source_bucket,
+                    source_key,
+                    dest_bucket,
+                    dest_key,
+                    source,
+                    replacement,
+                )
+                .await;
+        }
+
+        // A multipart copy starts from a fresh object, so the headers are always set explicitly
+        let source = match replacement {
+            Some(replacement) => source.replaced(replacement),
+            None => source,
+        };
+        self.copy_multipart(source_bucket, source_key, dest_bucket, dest_key, &source)
+            .await
+    }
+
+    // Fails when `key` exists, so copies and renames don't overwrite by accident
+    pub async fn ensure_absent(&self, bucket_name: &str, key: &str) -> Result<(), R2Error> {
+        match self.head_object(bucket_name, key).await {
+            Ok(_) => Err(R2Error::InvalidInput(tf("copy.target_exists", &[&key]))),
+            Err(R2Error::ObjectNotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn move_object(
+        &self,
+        source_bucket: &str,
+        source_key: &str,
+        dest_bucket: &str,
+        dest_key: &str,
+        replacement: Option<&MetadataReplacement>,
+    ) -> Result<(), R2Error> {
+        // Moving onto itself only changes anything when there is metadata to replace
+        if source_bucket == dest_bucket && source_key == dest_key {
+            if let Some(replacement) = replacement {
+                self.update_object_metadata(source_bucket, source_key, replacement)
+                    .await?;
+            }
+            return Ok(());
+        }
+
+        self.copy_object(
+            source_bucket,
+            source_key,
+            dest_bucket,
+            dest_key,
+            replacement,
+        )
+        .await?;
+        self.delete_object(source_bucket, source_key).await
+    }
+
+    // Rewrites an object's headers and user metadata in place via a REPLACE self-copy
+    pub async fn update_object_metadata(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        replacement: &MetadataReplacement,
+    ) -> Result<ObjectMetadata, R2Error> {
+        self.copy_object(bucket_name, key, bucket_name, key, Some(replacement))
+            .await?;
+        self.head_object(bucket_name, key).await
+    }
+
+    // Renames within the same folder; returns the new key
+    pub async fn rename_object(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        new_name: &str,
+    ) -> Result<String, R2Error> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() || new_name.contains('/') {
+            return Err(R2Error::InvalidInput(tf("copy.invalid_name", &[&new_name])));
+        }
+
+        let parent = key.rsplit_once('/').map(|(parent, _)| parent);
+        let new_key = match parent {
+            Some(parent) => format!("{}/{}", parent, new_name),
+            None => new_name.to_string(),
+        };
+        if new_key == key {
+            return Ok(new_key);
+        }
+
+        self.ensure_absent(bucket_name, &new_key).await?;
+        self.move_object(bucket_name, key, bucket_name, &new_key, None)
+            .await?;
+        Ok(new_key)
+    }
+
+    async fn copy_single(
+        &self,
+        source_bucket: &str,
+        source_key: &str,
+        dest_bucket: &str,
+        dest_key: &str,
+        source: ObjectMetadata,
+        replacement: Option<&MetadataReplacement>,
+    ) -> Result<(), R2Error> {
+        let request = self
+            .client
+            .copy_object()
+            .bucket(dest_bucket)
+            .key(dest_key)
+            .copy_source(copy_source(source_bucket, source_key))
+            .set_copy_source_if_match(source.etag.clone());
+
+        // REPLACE drops every header it isn't given, so the source's are passed back in
+        let request = match replacement {
+            Some(replacement) => {
+                let source = source.replaced(replacement);
+                request
+                    .metadata_directive(MetadataDirective::Replace)
+                    .set_content_type(source.content_type)
+                    .set_cache_control(source.cache_control)
+                    .set_content_disposition(source.content_disposition)
+                    .set_content_encoding(source.content_encoding)
+                    .set_metadata(Some(source.metadata))
+            }
+            None => request.metadata_directive(MetadataDirective::Copy),
+        };
+
+        request.send().await.map_err(R2Error::from_sdk)?;
+        Ok(())
+    }
+
+    async fn copy_multipart(
+        &self,
+        source_bucket: &str,
+        source_key: &str,
+        dest_bucket: &str,
+        dest_key: &str,
+        source: &ObjectMetadata,
+    ) -> Result<(), R2Error> {
+        let response = self
+            .client
+            .create_multipart_upload()
+            .bucket(dest_bucket)
+            .key(dest_key)
+            .set_content_type(source.content_type.clone())
+            .set_cache_control(source.cache_control.clone())
+            .set_content_disposition(source.content_disposition.clone())
+            .set_content_encoding(source.content_encoding.clone())
+            .set_metadata(Some(source.metadata.clone()))
+            .send()
+            .await
+            .map_err(R2Error::from_sdk)?;
+        let upload_id = response
+            .upload_id()
+            .ok_or_else(|| R2Error::Unknown(t("upload.missing_upload_id").to_string()))?
+            .to_string();
+
+        let part_size = COPY_PART_SIZE
+            .max(source.size.div_ceil(MAX_PARTS))
+            .min(MAX_PART_SIZE);
+        let ranges: Vec<(i32, u64, u64)> = (1..=source.size.div_ceil(part_size))
+            .map(|n| {
+                let start = (n - 1) * part_size;
+                (n as i32, start, (start + part_size).min(source.size) - 1)
+            })
+            .collect();
+
+        let (upload_id_ref, source_etag) = (upload_id.as_str(), source.etag.as_deref());
+        let result: Result<Vec<UploadedPart>, R2Error> = stream::iter(ranges)
+            .map(|(part_number, start, end)| {
+                self.copy_part(
+                    source_bucket,
+                    source_key,
+                    source_etag,
+                    dest_bucket,
+                    dest_key,
+                    upload_id_ref,
+                    part_number,
+                    (start, end),
+                )
+            })
+            .buffer_unordered(MAX_CONCURRENT_COPY_PARTS)
+            .try_collect()
+            .await;
+
+        let result = match result {
+            Ok(mut parts) => {
+                parts.sort_by_key(|p| p.part_number);
+                self.complete_multipart_upload(dest_bucket, dest_key, &upload_id, &parts)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+
+        if result.is_err() {
+            let _ = self
+                .abort_multipart_upload(dest_bucket, dest_key, &upload_id)
+                .await;
+        }
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn copy_part(
+        &self,
+        source_bucket: &str,
+        source_key: &str,
+        source_etag: Option<&str>,
+        dest_bucket: &str,
+        dest_key: &str,
+        upload_id: &str,
+        part_number: i32,
+        (start, end): (u64, u64),
+    ) -> Result<UploadedPart, R2Error> {
+        let response = self
+            .client
+            .upload_part_copy()
+            .bucket(dest_bucket)
+            .key(dest_key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .copy_source(copy_source(source_bucket, source_key))
+            .copy_source_range(format!("bytes={}-{}", start, end))
+            .set_copy_source_if_match(source_etag.map(|etag| etag.to_string()))
+            .send()
+            .await
+            .map_err(R2Error::from_sdk)?;
+
+        let etag = response
+            .copy_part_result()
+            .and_then(|result| result.e_tag())
+            .ok_or_else(|| R2Error::Unknown(tf("upload.part_missing_etag", &[&part_number])))?;
+
+        Ok(UploadedPart {
+            part_number,
+            etag: etag.to_string(),
+        })
+    }
+}
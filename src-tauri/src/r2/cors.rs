@@ -0,0 +1,231 @@
+use super::client::R2Client;
+use super::types::R2Error;
+use crate::i18n::tf;
+use aws_sdk_s3::error::ProvideErrorMetadata;
+use aws_sdk_s3::types::{CorsConfiguration, CorsRule as SdkCorsRule};
+use serde::{Deserialize, Serialize};
+
+// PutBucketCors rejects configurations with more rules than this
+pub const MAX_CORS_RULES: usize = 100;
+const ALLOWED_METHODS: &[&str] = &["GET", "PUT", "POST", "DELETE", "HEAD"];
+
+// Field aliases let rule sets exported by the AWS CLI (`get-bucket-cors`) be imported as is
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsRule {
+    #[serde(alias = "ID")]
+    pub id: Option<String>,
+    #[serde(alias = "AllowedOrigins")]
+    pub allowed_origins: Vec<String>,
+    #[serde(alias = "AllowedMethods")]
+    pub allowed_methods: Vec<String>,
+    #[serde(alias = "AllowedHeaders")]
+    pub allowed_headers: Vec<String>,
+    #[serde(alias = "ExposeHeaders")]
+    pub expose_headers: Vec<String>,
+    #[serde(alias = "MaxAgeSeconds")]
+    pub max_age_seconds: Option<i32>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CorsDocument {
+    Rules(Vec<CorsRule>),
+    Wrapped {
+        #[serde(alias = "CORSRules")]
+        rules: Vec<CorsRule>,
+    },
+}
+
+fn trimmed(values: &[String]) -> Vec<String> {
+    values
+        .iter()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+impl CorsRule {
+    // Drops blank entries and upper-cases methods, as typed in by hand or imported
+    fn normalized(&self) -> Self {
+        CorsRule {
+            id: self
+                .id
+                .as_deref()
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string),
+            allowed_origins: trimmed(&self.allowed_origins),
+            allowed_methods: trimmed(&self.allowed_methods)
+                .into_iter()
+                .map(|m| m.to_ascii_uppercase())
+                .collect(),
+            allowed_headers: trimmed(&self.allowed_headers),
+            expose_headers: trimmed(&self.expose_headers),
+            max_age_seconds: self.max_age_seconds,
+        }
+    }
+
+    // `index` is 1-based, as shown to the user
+    fn validate(&self, index: usize) -> Result<(), R2Error> {
+        let invalid = |key, value: &dyn std::fmt::Display| {
+            Err(R2Error::InvalidInput(tf(key, &[&index, value])))
+        };
+
+        if self.allowed_origins.is_empty() {
+            return Err(R2Error::InvalidInput(tf("cors.no_origins", &[&index])));
+        }
+        if self.allowed_methods.is_empty() {
+            return Err(R2Error::InvalidInput(tf("cors.no_methods", &[&index])));
+        }
+        if let Some(method) = self
+            .allowed_methods
+            .iter()
+            .find(|m| !ALLOWED_METHODS.contains(&m.as_str()))
+        {
+            return invalid("cors.invalid_method", method);
+        }
+        if let Some(origin) = self
+            .allowed_origins
+            .iter()
+            .find(|o| o.matches('*').count() > 1)
+        {
+            return invalid("cors.invalid_origin", origin);
+        }
+        if let Some(header) = self
+            .allowed_headers
+            .iter()
+            .find(|h| h.matches('*').count() > 1)
+        {
+            return invalid("cors.invalid_header", header);
+        }
+        // Exposed headers are sent back verbatim, so wildcards aren't allowed there
+        if let Some(header) = self.expose_headers.iter().find(|h| h.contains('*')) {
+            return invalid("cors.invalid_expose_header", header);
+        }
+        if let Some(max_age) = self.max_age_seconds.filter(|age| *age < 0) {
+            return invalid("cors.invalid_max_age", &max_age);
+        }
+        Ok(())
+    }
+
+    fn to_sdk(&self) -> Result<SdkCorsRule, R2Error> {
+        let non_empty = |values: &Vec<String>| (!values.is_empty()).then(|| values.clone());
+        SdkCorsRule::builder()
+            .set_id(self.id.clone())
+            .set_allowed_origins(Some(self.allowed_origins.clone()))
+            .set_allowed_methods(Some(self.allowed_methods.clone()))
+            .set_allowed_headers(non_empty(&self.allowed_headers))
+            .set_expose_headers(non_empty(&self.expose_headers))
+            .set_max_age_seconds(self.max_age_seconds)
+            .build()
+            .map_err(|e| R2Error::InvalidInput(e.to_string()))
+    }
+
+    fn from_sdk(rule: &SdkCorsRule) -> Self {
+        CorsRule {
+            id: rule.id().map(str::to_string),
+            allowed_origins: rule.allowed_origins().to_vec(),
+            allowed_methods: rule.allowed_methods().to_vec(),
+            allowed_headers: rule.allowed_headers().to_vec(),
+            expose_headers: rule.expose_headers().to_vec(),
+            max_age_seconds: rule.max_age_seconds(),
+        }
+    }
+}
+
+// Normalizes the rules and checks them locally, so mistakes get a specific message instead
+// of R2's generic MalformedXML
+pub fn validate_cors_rules(rules: &[CorsRule]) -> Result<Vec<CorsRule>, R2Error> {
+    if rules.len() > MAX_CORS_RULES {
+        return Err(R2Error::InvalidInput(tf(
+            "cors.too_many_rules",
+            &[&MAX_CORS_RULES],
+        )));
+    }
+
+    let rules: Vec<CorsRule> = rules.iter().map(CorsRule::normalized).collect();
+    for (index, rule) in rules.iter().enumerate() {
+        rule.validate(index + 1)?;
+    }
+    Ok(rules)
+}
+
+// Accepts a bare array of rules, or an object holding them under `rules` or `CORSRules`
+pub fn parse_cors_json(json: &str) -> Result<Vec<CorsRule>, R2Error> {
+    let document: CorsDocument = serde_json::from_str(json)
+        .map_err(|e| R2Error::InvalidInput(tf("cors.invalid_json", &[&e])))?;
+    let rules = match document {
+        CorsDocument::Rules(rules) | CorsDocument::Wrapped { rules } => rules,
+    };
+    validate_cors_rules(&rules)
+}
+
+pub fn cors_to_json(rules: &[CorsRule]) -> Result<String, R2Error> {
+    serde_json::to_string_pretty(rules).map_err(|e| R2Error::Unknown(e.to_string()))
+}
+
+impl R2Client {
+    // A bucket without a CORS configuration comes back as an empty rule set
+    pub async fn get_bucket_cors(&self, bucket_name: &str) -> Result<Vec<CorsRule>, R2Error> {
+        match self
+            .client
+            .get_bucket_cors()
+            .bucket(bucket_name)
+            .send()
+            .await
+        {
+            Ok(response) => Ok(response
+                .cors_rules()
+                .iter()
+                .map(CorsRule::from_sdk)
+                .collect()),
+            Err(e) if e.code() == Some("NoSuchCORSConfiguration") => Ok(Vec::new()),
+            Err(e) => Err(R2Error::from_sdk(e)),
+        }
+    }
+
+    // Replaces the whole configuration; an empty rule set removes it instead
+    pub async fn put_bucket_cors(
+        &self,
+        bucket_name: &str,
+        rules: &[CorsRule],
+    ) -> Result<Vec<CorsRule>, R2Error> {
+        let rules = validate_cors_rules(rules)?;
+        if rules.is_empty() {
+            self.delete_bucket_cors(bucket_name).await?;
+            return Ok(rules);
+        }
+
+        let configuration = CorsConfiguration::builder()
+            .set_cors_rules(Some(
+                rules
+                    .iter()
+                    .map(CorsRule::to_sdk)
+                    .collect::<Result<_, _>>()?,
+            ))
+            .build()
+            .map_err(|e| R2Error::InvalidInput(e.to_string()))?;
+
+        self.client
+            .put_bucket_cors()
+            .bucket(bucket_name)
+            .cors_configuration(configuration)
+            .send()
+            .await
+            .map_err(R2Error::from_sdk)?;
+
+        Ok(rules)
+    }
+
+    pub async fn delete_bucket_cors(&self, bucket_name: &str) -> Result<(), R2Error> {
+        self.client
+            .delete_bucket_cors()
+            .bucket(bucket_name)
+            .send()
+            .await
+            .map_err(R2Error::from_sdk)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+use super::client::R2Client;
+use super::control::TransferHooks;
+use super::retry::backoff;
+use super::types::R2Error;
+use crate::i18n::tf;
+use md5::{Digest, Md5};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+const CHECKPOINT_INTERVAL: u64 = 16 * 1024 * 1024;
+
+pub fn temp_path_for(save_path: &Path) -> PathBuf {
+    let mut file_name = save_path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    file_name.push(".part");
+    save_path.with_file_name(file_name)
+}
+
+// Multipart ETags ("<hash>-<parts>") are not a plain MD5 of the content and can't be verified
+fn expected_md5(etag: &str) -> Option<String> {
+    let etag = etag.trim_matches('"');
+    if etag.len() == 32 && etag.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(etag.to_ascii_lowercase())
+    } else {
+        None
+    }
+}
+
+fn is_stale_resume(error: &R2Error) -> bool {
+    match error {
+        R2Error::PreconditionFailed(_) => true,
+        R2Error::SdkError(detail) => {
+            detail.http_status == Some(416) || detail.s3_code.as_deref() == Some("InvalidRange")
+        }
+        _ => false,
+    }
+}
+
+pub struct DownloadResume {
+    pub etag: Option<String>,
+    pub offset: u64,
+}
+
+// Re-open a partial download, trimming anything past the last checkpoint
+async fn reopen_partial(temp_path: &Path, offset: u64) -> Result<Option<(File, Md5)>, R2Error> {
+    let Ok(mut file) = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(temp_path)
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let len = file
+        .metadata()
+        .await
+        .map_err(|e| R2Error::IoError(e.to_string()))?
+        .len();
+    if len < offset {
+        return Ok(None);
+    }
+
+    file.set_len(offset)
+        .await
+        .map_err(|e| R2Error::IoError(e.to_string()))?;
+
+    // The ETag check covers the whole object, so hash what is already on disk
+    let mut hasher = Md5::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| R2Error::IoError(e.to_string()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    file.seek(SeekFrom::Start(offset))
+        .await
+        .map_err(|e| R2Error::IoError(e.to_string()))?;
+
+    Ok(Some((file, hasher)))
+}
+
+impl R2Client {
+    pub async fn download_file(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        save_path: &Path,
+        resume: Option<DownloadResume>,
+        hooks: &TransferHooks<'_>,
+    ) -> Result<(), R2Error> {
+        let temp_path = temp_path_for(save_path);
+
+        let mut result = self
+            .download_to_temp(bucket_name, key, &temp_path, resume, hooks)
+            .await;
+        // Either the object changed under the pinned ETag, so the partial bytes are useless,
+        // or the journaled offset already reached the end and the ranged GET was refused.
+        // Start over in both cases.
+        if result.as_ref().is_err_and(is_stale_resume) {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            hooks.progress.restart();
+            result = self
+                .download_to_temp(bucket_name, key, &temp_path, None, hooks)
+                .await;
+        }
+
+        if result.is_ok() {
+            result = tokio::fs::rename(&temp_path, save_path)
+                .await
+                .map_err(|e| R2Error::IoError(e.to_string()));
+        }
+
+        // Journaled downloads keep the partial file so they can pick up where they stopped,
+        // but only when a retry could get further. Bad bytes on disk would fail every resume.
+        if let Err(e) = &result {
+            if hooks.checkpoint.is_none() || !e.is_retryable() {
+                let _ = tokio::fs::remove_file(&temp_path).await;
+            }
+        }
+        result
+    }
+
+    async fn download_to_temp(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        temp_path: &Path,
+        resume: Option<DownloadResume>,
+        hooks: &TransferHooks<'_>,
+    ) -> Result<(), R2Error> {
+        hooks.control.checkpoint().await?;
+
+        let resumed = match &resume {
+            Some(r) if r.offset > 0 => reopen_partial(temp_path, r.offset)
+                .await?
+                .map(|(file, hasher)| (file, hasher, r.offset, r.etag.clone())),
+            _ => None,
+        };
+
+        let (mut file, mut hasher, mut written, mut stream, total_bytes, etag) = match resumed {
+            Some((file, hasher, offset, etag)) => {
+                // Pinned to the journaled ETag so a changed object isn't spliced in
+                let stream = self
+                    .get_object_stream(bucket_name, key, Some(offset), etag.as_deref())
+                    .await?;
+                let total_bytes = offset + stream.content_length;
+                (file, hasher, offset, stream, total_bytes, etag)
+            }
+            None => {
+                let stream = self.get_object_stream(bucket_name, key, None, None).await?;
+                let file = File::create(temp_path)
+                    .await
+                    .map_err(|e| R2Error::IoError(e.to_string()))?;
+                let total_bytes = stream.content_length;
+                let etag = stream.etag.clone();
+                if let Some(checkpoint) = hooks.checkpoint {
+                    checkpoint.download_started(etag.as_deref(), total_bytes);
+                }
+                (file, Md5::new(), 0, stream, total_bytes, etag)
+            }
+        };
+
+        hooks.progress.set_total(total_bytes);
+        hooks.progress.advance(written);
+
+        let mut last_checkpoint = written;
+        let mut failed_attempts = 0;
+
+        while written < total_bytes {
+            let next = tokio::select! {
+                chunk = stream.body.try_next() => Some(chunk),
+                _ = hooks.control.interrupted() => None,
+            };
+
+            let Some(chunk) = next else {
+                // Drop the connection while paused and pick up where we left off
+                sync_checkpoint(&file, written, hooks).await?;
+                last_checkpoint = written;
+                hooks.control.checkpoint().await?;
+                stream = self
+                    .get_object_stream(bucket_name, key, Some(written), etag.as_deref())
+                    .await?;
+                continue;
+            };
+
+            let chunk = match chunk {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    // The SDK can't replay a body cut off midway, so reconnect from `written`.
+                    // The GET itself is retried by the SDK; only the cut-offs are counted here.
+                    failed_attempts += 1;
+                    if failed_attempts >= self.network().max_attempts() {
+                        return Err(R2Error::NetworkError(e.to_string().into()));
+                    }
+                    sync_checkpoint(&file, written, hooks).await?;
+                    last_checkpoint = written;
+                    tokio::select! {
+                        _ = tokio::time::sleep(backoff(failed_attempts)) => {}
+                        _ = hooks.control.interrupted() => {}
+                    }
+                    hooks.control.checkpoint().await?;
+                    stream = self
+                        .get_object_stream(bucket_name, key, Some(written), etag.as_deref())
+                        .await?;
+                    continue;
+                }
+            };
+            failed_attempts = 0;
+
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| R2Error::IoError(e.to_string()))?;
+            hasher.update(&chunk);
+            written += chunk.len() as u64;
+            hooks.progress.advance(chunk.len() as u64);
+
+            if written - last_checkpoint >= CHECKPOINT_INTERVAL {
+                sync_checkpoint(&file, written, hooks).await?;
+                last_checkpoint = written;
+            }
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| R2Error::IoError(e.to_string()))?;
+        file.sync_all()
+            .await
+            .map_err(|e| R2Error::IoError(e.to_string()))?;
+
+        if written != total_bytes {
+            return Err(R2Error::IntegrityError(tf(
+                "download.size_mismatch",
+                &[&total_bytes, &written],
+            )));
+        }
+
+        if let Some(expected) = etag.as_deref().and_then(expected_md5) {
+            let actual = format!("{:x}", hasher.finalize());
+            if actual != expected {
+                return Err(R2Error::IntegrityError(tf(
+                    "download.etag_mismatch",
+                    &[&expected, &actual],
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Only offsets whose bytes have reached the disk are recorded in the journal
+async fn sync_checkpoint(
+    file: &File,
+    written: u64,
+    hooks: &TransferHooks<'_>,
+) -> Result<(), R2Error> {
+    let Some(checkpoint) = hooks.checkpoint else {
+        return Ok(());
+    };
+
+    file.sync_data()
+        .await
+        .map_err(|e| R2Error::IoError(e.to_string()))?;
+    checkpoint.download_checkpoint(written);
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use super::types::{ErrorDetail, R2Error};
+use crate::storage::config::ConfigError;
+use aws_sdk_s3::config::http::HttpResponse;
+use aws_sdk_s3::error::{DisplayErrorContext, ProvideErrorMetadata, SdkError};
+use aws_sdk_s3::operation::RequestId;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+const CREDENTIAL_CODES: &[&str] = &[
+    "AccessDenied",
+    "InvalidAccessKeyId",
+    "SignatureDoesNotMatch",
+    "InvalidToken",
+    "ExpiredToken",
+    "Unauthorized",
+];
+
+const RETRYABLE_CODES: &[&str] = &[
+    "InternalError",
+    "ServiceUnavailable",
+    "SlowDown",
+    "RequestTimeout",
+    "RequestTimeTooSkewed",
+];
+
+impl R2Error {
+    // Sorts an SDK failure into the variant the frontend can act on
+    pub fn from_sdk<E>(err: SdkError<E, HttpResponse>) -> Self
+    where
+        E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+    {
+        let http_status = err.raw_response().map(|r| r.status().as_u16());
+        let detail = ErrorDetail {
+            message: err
+                .message()
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| DisplayErrorContext(&err).to_string()),
+            http_status,
+            s3_code: err.code().map(|c| c.to_string()),
+            request_id: err.request_id().map(|id| id.to_string()),
+        };
+
+        if matches!(
+            err,
+            SdkError::DispatchFailure(_) | SdkError::TimeoutError(_)
+        ) {
+            return R2Error::NetworkError(detail);
+        }
+
+        Self::classify(detail)
+    }
+
+    // Per-key failures inside a DeleteObjects response carry only a code and a message
+    pub fn from_key_error(code: Option<&str>, message: Option<&str>) -> Self {
+        Self::classify(ErrorDetail {
+            message: message.or(code).unwrap_or_default().to_string(),
+            s3_code: code.map(|c| c.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn classify(detail: ErrorDetail) -> Self {
+        match (detail.s3_code.as_deref(), detail.http_status) {
+            (Some("NoSuchBucket"), _) => R2Error::BucketNotFound(detail),
+            (Some("NoSuchKey" | "NotFound"), _) => R2Error::ObjectNotFound(detail),
+            (Some("PreconditionFailed"), _) | (_, Some(412)) => R2Error::PreconditionFailed(detail),
+            (Some(code), _) if CREDENTIAL_CODES.contains(&code) => {
+                R2Error::CredentialsError(detail)
+            }
+            (_, Some(401 | 403)) => R2Error::CredentialsError(detail),
+            // HEAD responses have no body, so a bare 404 is all there is to go on
+            (None, Some(404)) => R2Error::ObjectNotFound(detail),
+            _ => R2Error::SdkError(detail),
+        }
+    }
+
+    // Stable identifier the frontend can match on; never localized
+    pub fn code(&self) -> &'static str {
+        match self {
+            R2Error::SdkError(_) => "sdk_error",
+            R2Error::CredentialsError(_) => "credentials_error",
+            R2Error::BucketNotFound(_) => "bucket_not_found",
+            R2Error::ObjectNotFound(_) => "object_not_found",
+            R2Error::NetworkError(_) => "network_error",
+            R2Error::PreconditionFailed(_) => "precondition_failed",
+            R2Error::IoError(_) => "io_error",
+            R2Error::IntegrityError(_) => "integrity_error",
+            R2Error::InvalidInput(_) => "invalid_input",
+            R2Error::ConfigError(_) => "config_error",
+            R2Error::VaultLocked => "vault_locked",
+            R2Error::TransferNotFound => "transfer_not_found",
+            R2Error::Cancelled => "cancelled",
+            R2Error::Unknown(_) => "unknown",
+        }
+    }
+
+    pub fn detail(&self) -> Option<&ErrorDetail> {
+        match self {
+            R2Error::SdkError(detail)
+            | R2Error::CredentialsError(detail)
+            | R2Error::BucketNotFound(detail)
+            | R2Error::ObjectNotFound(detail)
+            | R2Error::NetworkError(detail)
+            | R2Error::PreconditionFailed(detail) => Some(detail),
+            _ => None,
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            R2Error::NetworkError(_) => true,
+            R2Error::SdkError(detail) => {
+                matches!(detail.http_status, Some(429 | 500..=599))
+                    || detail
+                        .s3_code
+                        .as_deref()
+                        .is_some_and(|code| RETRYABLE_CODES.contains(&code))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Serialize for R2Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let detail = self.detail();
+        let mut state = serializer.serialize_struct("R2Error", 6)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("http_status", &detail.and_then(|d| d.http_status))?;
+        state.serialize_field("s3_code", &detail.and_then(|d| d.s3_code.as_deref()))?;
+        state.serialize_field("request_id", &detail.and_then(|d| d.request_id.as_deref()))?;
+        state.serialize_field("retryable", &self.is_retryable())?;
+        state.end()
+    }
+}
+
+impl From<ConfigError> for R2Error {
+    fn from(err: ConfigError) -> Self {
+        match err {
+            ConfigError::VaultLocked => R2Error::VaultLocked,
+            ConfigError::Io(e) => R2Error::IoError(e.to_string()),
+            other => R2Error::ConfigError(other.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for R2Error {
+    fn from(err: std::io::Error) -> Self {
+        R2Error::IoError(err.to_string())
+    }
+}
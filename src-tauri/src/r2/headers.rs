@@ -0,0 +1,131 @@
+use super::types::R2Error;
+use crate::i18n::tf;
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+// Enough of the file for every signature `infer` knows
+const SNIFF_LEN: usize = 8 * 1024;
+
+// HTTP headers and custom metadata stored with an uploaded object. An empty string unsets a
+// value that a matching rule would otherwise apply.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UploadHeaders {
+    pub content_type: Option<String>,
+    pub cache_control: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_encoding: Option<String>,
+    pub metadata: HashMap<String, String>,
+}
+
+// Applies `headers` to every key matching `pattern`; later rules win over earlier ones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderRule {
+    pub pattern: String,
+    #[serde(default)]
+    pub headers: UploadHeaders,
+}
+
+// Rejects rules whose pattern would never match, before they are saved
+pub fn validate_rules(rules: &[HeaderRule]) -> Result<(), R2Error> {
+    for rule in rules {
+        Pattern::new(rule.pattern.trim())
+            .map_err(|e| R2Error::InvalidInput(tf("file.invalid_pattern", &[&rule.pattern, &e])))?;
+    }
+    Ok(())
+}
+
+fn overlay(value: &mut Option<String>, other: &Option<String>) {
+    if other.is_some() {
+        value.clone_from(other);
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+impl UploadHeaders {
+    fn overlay(&mut self, other: &UploadHeaders) {
+        overlay(&mut self.content_type, &other.content_type);
+        overlay(&mut self.cache_control, &other.cache_control);
+        overlay(&mut self.content_disposition, &other.content_disposition);
+        overlay(&mut self.content_encoding, &other.content_encoding);
+        self.metadata.extend(
+            other
+                .metadata
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+    }
+
+    // Rules are matched against the full key, and `*` also crosses "/", so "*.html" covers
+    // every HTML file in the bucket. Explicit per-upload headers take precedence over rules.
+    pub fn resolve(key: &str, rules: &[HeaderRule], explicit: &UploadHeaders) -> Self {
+        let mut headers = UploadHeaders::default();
+        for rule in rules {
+            let matched = Pattern::new(rule.pattern.trim())
+                .map(|pattern| pattern.matches(key))
+                .unwrap_or(false);
+            if matched {
+                headers.overlay(&rule.headers);
+            }
+        }
+        headers.overlay(explicit);
+
+        UploadHeaders {
+            content_type: non_empty(headers.content_type),
+            cache_control: non_empty(headers.cache_control),
+            content_disposition: non_empty(headers.content_disposition),
+            content_encoding: non_empty(headers.content_encoding),
+            metadata: headers
+                .metadata
+                .into_iter()
+                .map(|(name, value)| (name.trim().to_ascii_lowercase(), value))
+                .filter(|(name, _)| !name.is_empty())
+                .collect(),
+        }
+    }
+
+    // Fills in the Content-Type when neither a rule nor the caller chose one
+    pub fn with_detected_type(mut self, key: &str, head: &[u8]) -> Self {
+        if self.content_type.is_none() {
+            self.content_type = detect_content_type(key, head);
+        }
+        self
+    }
+}
+
+// The extension decides first, since text formats such as HTML, CSS and JS have no magic
+// bytes; the file header covers keys without a known extension
+pub fn detect_content_type(key: &str, head: &[u8]) -> Option<String> {
+    let mime = mime_guess::from_path(key)
+        .first_raw()
+        .map(str::to_string)
+        .or_else(|| infer::get(head).map(|kind| kind.mime_type().to_string()))?;
+
+    // Browsers guess the charset of text served without one, which garbles non-ASCII pages
+    let utf8 = match std::str::from_utf8(head) {
+        Ok(_) => true,
+        // The sample may end partway through a character
+        Err(e) => e.error_len().is_none(),
+    };
+    if utf8 && (mime.starts_with("text/") || mime == "application/javascript") {
+        return Some(format!("{}; charset=utf-8", mime));
+    }
+    Some(mime)
+}
+
+// Reads the start of a file for magic byte detection; an unreadable file just isn't sniffed
+pub async fn read_head(path: &Path) -> Vec<u8> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    if let Ok(file) = tokio::fs::File::open(path).await {
+        let _ = file.take(SNIFF_LEN as u64).read_to_end(&mut head).await;
+    }
+    head
+}
@@ -0,0 +1,502 @@
+use super::client::R2Client;
+use super::types::R2Error;
+use crate::i18n::tf;
+use aws_sdk_s3::error::ProvideErrorMetadata;
+use aws_sdk_s3::primitives::DateTime;
+use aws_sdk_s3::types::{
+    AbortIncompleteMultipartUpload, BucketLifecycleConfiguration, ExpirationStatus,
+    LifecycleExpiration, LifecycleRule as SdkLifecycleRule, LifecycleRuleAndOperator,
+    LifecycleRuleFilter,
+};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+// PutBucketLifecycleConfiguration rejects configurations with more rules than this
+pub const MAX_LIFECYCLE_RULES: usize = 1000;
+const MAX_RULE_ID_LEN: usize = 255;
+// Preview samples are capped; the counts still cover every matching object
+const PREVIEW_SAMPLE_LIMIT: usize = 100;
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+// Only the actions R2 applies are modelled. Anything else stored on a rule, such as
+// storage class transitions, noncurrent-version actions, delete-marker expiry or tag and
+// size conditions in its filter, is carried over untouched when the rule is edited.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifecycleRule {
+    pub id: String,
+    pub enabled: bool,
+    // Empty applies the rule to the whole bucket
+    pub prefix: String,
+    pub expiration_days: Option<i32>,
+    // YYYY-MM-DD, UTC
+    pub expiration_date: Option<String>,
+    pub abort_multipart_days: Option<i32>,
+}
+
+impl Default for LifecycleRule {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            enabled: true,
+            prefix: String::new(),
+            expiration_days: None,
+            expiration_date: None,
+            abort_multipart_days: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LifecycleMatch {
+    pub key: String,
+    pub size: i64,
+    pub last_modified: String,
+    // None when the rule doesn't expire objects
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LifecyclePreview {
+    pub matched_objects: u64,
+    pub matched_bytes: u64,
+    // Already past the rule's threshold, so removed on R2's next lifecycle run
+    pub expiring_objects: u64,
+    pub expiring_bytes: u64,
+    pub incomplete_uploads: u64,
+    // Soonest to expire first
+    pub sample: Vec<LifecycleMatch>,
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, R2Error> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| R2Error::InvalidInput(tf("lifecycle.invalid_date", &[&date])))
+}
+
+fn date_secs(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
+        .timestamp()
+}
+
+fn format_secs(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0)
+        .unwrap_or_default()
+        .to_rfc3339()
+}
+
+impl LifecycleRule {
+    fn normalized(&self) -> Self {
+        LifecycleRule {
+            id: self.id.trim().to_string(),
+            prefix: self.prefix.trim_start_matches('/').to_string(),
+            expiration_date: self
+                .expiration_date
+                .as_deref()
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string),
+            ..self.clone()
+        }
+    }
+
+    fn validate(&self) -> Result<(), R2Error> {
+        if self.id.is_empty() || self.id.chars().count() > MAX_RULE_ID_LEN {
+            return Err(R2Error::InvalidInput(tf(
+                "lifecycle.invalid_id",
+                &[&MAX_RULE_ID_LEN],
+            )));
+        }
+        self.validate_actions()
+    }
+
+    // Everything but the ID, which an unsaved rule being previewed may not have yet
+    fn validate_actions(&self) -> Result<(), R2Error> {
+        let invalid =
+            |key, args: &[&dyn std::fmt::Display]| Err(R2Error::InvalidInput(tf(key, args)));
+
+        if self.expiration_days.is_some() && self.expiration_date.is_some() {
+            return invalid("lifecycle.conflicting_expiration", &[&self.id]);
+        }
+        if self.expiration_days.is_none()
+            && self.expiration_date.is_none()
+            && self.abort_multipart_days.is_none()
+        {
+            return invalid("lifecycle.no_action", &[&self.id]);
+        }
+        for days in [self.expiration_days, self.abort_multipart_days]
+            .into_iter()
+            .flatten()
+        {
+            if days < 1 {
+                return invalid("lifecycle.invalid_days", &[&self.id, &days]);
+            }
+        }
+        if let Some(date) = &self.expiration_date {
+            parse_date(date)?;
+        }
+        Ok(())
+    }
+
+    // Seconds since the epoch at which an object modified at `modified` expires
+    fn expires_at(&self, modified: i64) -> Option<i64> {
+        if let Some(days) = self.expiration_days {
+            return Some(modified + i64::from(days) * DAY_SECS);
+        }
+        let date = parse_date(self.expiration_date.as_deref()?).ok()?;
+        Some(date_secs(date))
+    }
+
+    // `existing` is the stored rule being replaced, whose unmodelled actions are kept
+    fn to_sdk(&self, existing: Option<&SdkLifecycleRule>) -> Result<SdkLifecycleRule, R2Error> {
+        let expiration = match (&self.expiration_date, self.expiration_days) {
+            (Some(date), _) => Some(
+                LifecycleExpiration::builder()
+                    .date(DateTime::from_secs(date_secs(parse_date(date)?)))
+                    .build(),
+            ),
+            (None, Some(days)) => Some(LifecycleExpiration::builder().days(days).build()),
+            // Delete-marker expiry can't be combined with days or a date, so it only
+            // survives on a rule that doesn't expire objects
+            (None, None) => existing
+                .and_then(|rule| rule.expiration())
+                .and_then(|e| e.expired_object_delete_marker())
+                .map(|marker| {
+                    LifecycleExpiration::builder()
+                        .expired_object_delete_marker(marker)
+                        .build()
+                }),
+        };
+        let status = if self.enabled {
+            ExpirationStatus::Enabled
+        } else {
+            ExpirationStatus::Disabled
+        };
+
+        SdkLifecycleRule::builder()
+            .id(&self.id)
+            .status(status)
+            .filter(self.filter_for(existing.and_then(|rule| rule.filter())))
+            .set_expiration(expiration)
+            .set_abort_incomplete_multipart_upload(self.abort_multipart_days.map(|days| {
+                AbortIncompleteMultipartUpload::builder()
+                    .days_after_initiation(days)
+                    .build()
+            }))
+            .set_transitions(existing.map(|rule| rule.transitions().to_vec()))
+            .set_noncurrent_version_transitions(
+                existing.map(|rule| rule.noncurrent_version_transitions().to_vec()),
+            )
+            .set_noncurrent_version_expiration(
+                existing.and_then(|rule| rule.noncurrent_version_expiration().cloned()),
+            )
+            .build()
+            .map_err(|e| R2Error::InvalidInput(e.to_string()))
+    }
+
+    // Swaps the prefix into the stored filter, keeping its tag and size conditions. A filter
+    // holds a single condition, so a prefix joining others goes into an `And`.
+    fn filter_for(&self, existing: Option<&LifecycleRuleFilter>) -> LifecycleRuleFilter {
+        let prefix = Some(self.prefix.clone()).filter(|p| !p.is_empty());
+        let (tags, greater_than, less_than) = match existing {
+            Some(filter) => match filter.and() {
+                Some(and) => (
+                    and.tags().to_vec(),
+                    and.object_size_greater_than(),
+                    and.object_size_less_than(),
+                ),
+                None => (
+                    filter.tag().cloned().into_iter().collect(),
+                    filter.object_size_greater_than(),
+                    filter.object_size_less_than(),
+                ),
+            },
+            None => (Vec::new(), None, None),
+        };
+
+        let conditions = usize::from(prefix.is_some())
+            + tags.len()
+            + usize::from(greater_than.is_some())
+            + usize::from(less_than.is_some());
+        if conditions <= 1 {
+            return LifecycleRuleFilter::builder()
+                .set_tag(tags.into_iter().next())
+                .set_object_size_greater_than(greater_than)
+                .set_object_size_less_than(less_than)
+                // An empty prefix stands for "the whole bucket" only when nothing else filters
+                .set_prefix(prefix.or_else(|| (conditions == 0).then(String::new)))
+                .build();
+        }
+
+        LifecycleRuleFilter::builder()
+            .and(
+                LifecycleRuleAndOperator::builder()
+                    .set_prefix(prefix)
+                    .set_tags(Some(tags))
+                    .set_object_size_greater_than(greater_than)
+                    .set_object_size_less_than(less_than)
+                    .build(),
+            )
+            .build()
+    }
+
+    fn from_sdk(rule: &SdkLifecycleRule) -> Self {
+        // Older configurations put the prefix on the rule itself rather than in a filter
+        #[allow(deprecated)]
+        let prefix = rule
+            .filter()
+            .and_then(|filter| {
+                filter
+                    .prefix()
+                    .or(filter.and().and_then(|and| and.prefix()))
+            })
+            .or(rule.prefix())
+            .unwrap_or_default();
+        let expiration = rule.expiration();
+
+        LifecycleRule {
+            id: rule.id().unwrap_or_default().to_string(),
+            enabled: *rule.status() == ExpirationStatus::Enabled,
+            prefix: prefix.to_string(),
+            expiration_days: expiration.and_then(|e| e.days()),
+            expiration_date: expiration
+                .and_then(|e| e.date())
+                .and_then(|date| chrono::DateTime::from_timestamp(date.secs(), 0))
+                .map(|date| date.format("%Y-%m-%d").to_string()),
+            abort_multipart_days: rule
+                .abort_incomplete_multipart_upload()
+                .and_then(|abort| abort.days_after_initiation()),
+        }
+    }
+}
+
+fn stored_id(rule: &SdkLifecycleRule) -> &str {
+    rule.id().unwrap_or_default()
+}
+
+impl R2Client {
+    async fn get_lifecycle_configuration(
+        &self,
+        bucket_name: &str,
+    ) -> Result<Vec<SdkLifecycleRule>, R2Error> {
+        match self
+            .client
+            .get_bucket_lifecycle_configuration()
+            .bucket(bucket_name)
+            .send()
+            .await
+        {
+            Ok(response) => Ok(response.rules().to_vec()),
+            Err(e) if e.code() == Some("NoSuchLifecycleConfiguration") => Ok(Vec::new()),
+            Err(e) => Err(R2Error::from_sdk(e)),
+        }
+    }
+
+    // An empty rule set removes the configuration, as S3 rejects an empty one
+    async fn put_lifecycle_configuration(
+        &self,
+        bucket_name: &str,
+        rules: Vec<SdkLifecycleRule>,
+    ) -> Result<Vec<LifecycleRule>, R2Error> {
+        if rules.len() > MAX_LIFECYCLE_RULES {
+            return Err(R2Error::InvalidInput(tf(
+                "lifecycle.too_many_rules",
+                &[&MAX_LIFECYCLE_RULES],
+            )));
+        }
+
+        let saved = rules.iter().map(LifecycleRule::from_sdk).collect();
+        if rules.is_empty() {
+            self.client
+                .delete_bucket_lifecycle()
+                .bucket(bucket_name)
+                .send()
+                .await
+                .map_err(R2Error::from_sdk)?;
+            return Ok(saved);
+        }
+
+        let configuration = BucketLifecycleConfiguration::builder()
+            .set_rules(Some(rules))
+            .build()
+            .map_err(|e| R2Error::InvalidInput(e.to_string()))?;
+        self.client
+            .put_bucket_lifecycle_configuration()
+            .bucket(bucket_name)
+            .lifecycle_configuration(configuration)
+            .send()
+            .await
+            .map_err(R2Error::from_sdk)?;
+
+        Ok(saved)
+    }
+
+    pub async fn list_lifecycle_rules(
+        &self,
+        bucket_name: &str,
+    ) -> Result<Vec<LifecycleRule>, R2Error> {
+        let rules = self.get_lifecycle_configuration(bucket_name).await?;
+        Ok(rules.iter().map(LifecycleRule::from_sdk).collect())
+    }
+
+    // Each edit rewrites the whole configuration, so the rule set is read back first
+    pub async fn add_lifecycle_rule(
+        &self,
+        bucket_name: &str,
+        rule: &LifecycleRule,
+    ) -> Result<Vec<LifecycleRule>, R2Error> {
+        let rule = rule.normalized();
+        rule.validate()?;
+
+        let mut rules = self.get_lifecycle_configuration(bucket_name).await?;
+        if rules.iter().any(|r| stored_id(r) == rule.id) {
+            return Err(R2Error::InvalidInput(tf(
+                "lifecycle.duplicate_id",
+                &[&rule.id],
+            )));
+        }
+        rules.push(rule.to_sdk(None)?);
+
+        self.put_lifecycle_configuration(bucket_name, rules).await
+    }
+
+    // `rule_id` names the rule to replace; `rule.id` may rename it
+    pub async fn update_lifecycle_rule(
+        &self,
+        bucket_name: &str,
+        rule_id: &str,
+        rule: &LifecycleRule,
+    ) -> Result<Vec<LifecycleRule>, R2Error> {
+        let rule = rule.normalized();
+        rule.validate()?;
+
+        let mut rules = self.get_lifecycle_configuration(bucket_name).await?;
+        if rule.id != rule_id && rules.iter().any(|r| stored_id(r) == rule.id) {
+            return Err(R2Error::InvalidInput(tf(
+                "lifecycle.duplicate_id",
+                &[&rule.id],
+            )));
+        }
+        let index = rules
+            .iter()
+            .position(|r| stored_id(r) == rule_id)
+            .ok_or_else(|| R2Error::InvalidInput(tf("lifecycle.rule_not_found", &[&rule_id])))?;
+        rules[index] = rule.to_sdk(Some(&rules[index]))?;
+
+        self.put_lifecycle_configuration(bucket_name, rules).await
+    }
+
+    pub async fn remove_lifecycle_rule(
+        &self,
+        bucket_name: &str,
+        rule_id: &str,
+    ) -> Result<Vec<LifecycleRule>, R2Error> {
+        let mut rules = self.get_lifecycle_configuration(bucket_name).await?;
+        let before = rules.len();
+        rules.retain(|r| stored_id(r) != rule_id);
+        if rules.len() == before {
+            return Err(R2Error::InvalidInput(tf(
+                "lifecycle.rule_not_found",
+                &[&rule_id],
+            )));
+        }
+
+        self.put_lifecycle_configuration(bucket_name, rules).await
+    }
+
+    // Evaluates a rule, saved or not, against the bucket's current objects. R2 runs lifecycle
+    // rules about once a day, so "expiring" means due on the next run rather than gone now.
+    pub async fn preview_lifecycle_rule(
+        &self,
+        bucket_name: &str,
+        rule: &LifecycleRule,
+    ) -> Result<LifecyclePreview, R2Error> {
+        let rule = rule.normalized();
+        rule.validate_actions()?;
+
+        let now = Utc::now().timestamp();
+        let mut preview = LifecyclePreview::default();
+        let mut matches = Vec::new();
+
+        let objects = self
+            .list_objects_recursive(bucket_name, &rule.prefix)
+            .await?;
+        for object in objects {
+            let size = object.size.max(0) as u64;
+            let expires_at = chrono::DateTime::parse_from_rfc3339(&object.last_modified)
+                .ok()
+                .and_then(|modified| rule.expires_at(modified.timestamp()));
+
+            preview.matched_objects += 1;
+            preview.matched_bytes += size;
+            if expires_at.is_some_and(|at| at <= now) {
+                preview.expiring_objects += 1;
+                preview.expiring_bytes += size;
+            }
+            matches.push((expires_at, object));
+        }
+
+        matches.sort_by_key(|(expires_at, _)| expires_at.unwrap_or(i64::MAX));
+        preview.sample = matches
+            .into_iter()
+            .take(PREVIEW_SAMPLE_LIMIT)
+            .map(|(expires_at, object)| LifecycleMatch {
+                key: object.key,
+                size: object.size,
+                last_modified: object.last_modified,
+                expires_at: expires_at.map(format_secs),
+            })
+            .collect();
+
+        if let Some(days) = rule.abort_multipart_days {
+            let cutoff = now - i64::from(days) * DAY_SECS;
+            preview.incomplete_uploads = self
+                .count_multipart_uploads_before(bucket_name, &rule.prefix, cutoff)
+                .await?;
+        }
+
+        Ok(preview)
+    }
+
+    async fn count_multipart_uploads_before(
+        &self,
+        bucket_name: &str,
+        prefix: &str,
+        cutoff: i64,
+    ) -> Result<u64, R2Error> {
+        let mut count = 0;
+        let mut key_marker: Option<String> = None;
+        let mut upload_id_marker: Option<String> = None;
+
+        loop {
+            let response = self
+                .client
+                .list_multipart_uploads()
+                .bucket(bucket_name)
+                .prefix(prefix)
+                .set_key_marker(key_marker.take())
+                .set_upload_id_marker(upload_id_marker.take())
+                .send()
+                .await
+                .map_err(R2Error::from_sdk)?;
+
+            count += response
+                .uploads()
+                .iter()
+                .filter(|upload| upload.initiated().is_some_and(|at| at.secs() <= cutoff))
+                .count() as u64;
+
+            if !response.is_truncated().unwrap_or(false) {
+                break;
+            }
+            key_marker = response.next_key_marker().map(|m| m.to_string());
+            upload_id_marker = response.next_upload_id_marker().map(|m| m.to_string());
+            if key_marker.is_none() {
+                break;
+            }
+        }
+
+        Ok(count)
+    }
+}
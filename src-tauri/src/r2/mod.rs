@@ -1,2 +1,13 @@
 pub mod client;
+pub mod control;
+pub mod copy;
+pub mod cors;
+pub mod download;
+pub mod error;
+pub mod headers;
+pub mod lifecycle;
+pub mod pool;
+pub mod prefix;
+pub mod retry;
 pub mod types;
+pub mod upload;
@@ -0,0 +1,124 @@
+use super::client::R2Client;
+use super::types::{EndpointConfig, Jurisdiction, R2Error};
+use crate::i18n::t;
+use crate::storage::config::{Account, ConfigStore};
+use crate::storage::vault;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// One client per account, so every command shares its connection pool and TLS sessions
+#[derive(Default)]
+pub struct ClientPool {
+    clients: Mutex<HashMap<String, Pooled>>,
+}
+
+struct Pooled {
+    client: Arc<R2Client>,
+    // Lock generation of the vault session the secret was decrypted under, if it came from
+    // the vault
+    vault_generation: Option<u64>,
+}
+
+impl Pooled {
+    // Handing out a vault-backed client keeps the vault from idling out under it
+    fn is_live(&self) -> bool {
+        self.vault_generation.is_none_or(vault::touch)
+    }
+}
+
+impl ClientPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn get(&self, account_id: &str) -> Result<Arc<R2Client>, R2Error> {
+        {
+            let mut clients = self.clients.lock().unwrap();
+            match clients.get(account_id) {
+                Some(pooled) if pooled.is_live() => return Ok(pooled.client.clone()),
+                Some(_) => {
+                    clients.remove(account_id);
+                }
+                None => {}
+            }
+        }
+
+        // Read before the secret, so a lock in between marks this client stale
+        let generation = vault::generation();
+        let account = load_account(account_id)?;
+        let pooled = Pooled {
+            client: Arc::new(build_client(&account, &account.endpoint).await?),
+            vault_generation: account.secret_in_vault.then_some(generation),
+        };
+
+        // Another command may have raced us here; keep whichever client landed first
+        Ok(self
+            .clients
+            .lock()
+            .unwrap()
+            .entry(account_id.to_string())
+            .or_insert(pooled)
+            .client
+            .clone())
+    }
+
+    // Buckets in another jurisdiction are only reachable through that jurisdiction's endpoint,
+    // so a client for it is built on demand and not cached
+    pub async fn get_for_jurisdiction(
+        &self,
+        account_id: &str,
+        jurisdiction: Jurisdiction,
+    ) -> Result<Arc<R2Client>, R2Error> {
+        let account = load_account(account_id)?;
+        if account.endpoint.jurisdiction == jurisdiction {
+            return self.get(account_id).await;
+        }
+        let custom_endpoint = account.endpoint.custom_endpoint.as_deref();
+        if custom_endpoint.is_some_and(|e| !e.trim().is_empty()) {
+            return Err(R2Error::InvalidInput(
+                t("bucket.jurisdiction_custom_endpoint").to_string(),
+            ));
+        }
+
+        let mut endpoint = account.endpoint.clone();
+        endpoint.jurisdiction = jurisdiction;
+        Ok(Arc::new(build_client(&account, &endpoint).await?))
+    }
+
+    // Drop the cached client after the account's credentials change or it is removed
+    pub fn invalidate(&self, account_id: &str) {
+        self.clients.lock().unwrap().remove(account_id);
+    }
+
+    pub fn clear(&self) {
+        self.clients.lock().unwrap().clear();
+    }
+
+    // Drop clients whose vault session has ended, however it ended
+    pub fn drop_locked(&self) {
+        let current = vault::generation();
+        self.clients
+            .lock()
+            .unwrap()
+            .retain(|_, p| p.vault_generation.is_none_or(|g| g == current));
+    }
+}
+
+fn load_account(account_id: &str) -> Result<Account, R2Error> {
+    ConfigStore::new()?
+        .get_account(account_id)?
+        .ok_or_else(|| R2Error::CredentialsError(t("account.not_found").into()))
+}
+
+async fn build_client(account: &Account, endpoint: &EndpointConfig) -> Result<R2Client, R2Error> {
+    let network = ConfigStore::new()?.get_settings()?.network;
+
+    R2Client::new(
+        &account.account_id,
+        &account.access_key_id,
+        &account.secret_access_key,
+        endpoint,
+        &network,
+    )
+    .await
+}
@@ -0,0 +1,221 @@
+use super::client::{R2Client, DELETE_BATCH_SIZE};
+use super::types::{BatchPhase, BatchProgressSink, KeyError, R2Error};
+use crate::i18n::t;
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+
+const MAX_CONCURRENT_COPIES: usize = 8;
+
+#[derive(Debug, Default, Serialize)]
+pub struct PrefixMoveResult {
+    pub moved: u64,
+    pub failed: Vec<KeyError>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PrefixDeleteResult {
+    pub deleted: u64,
+    pub failed: Vec<KeyError>,
+}
+
+// Folder prefixes always end in exactly one `/`
+pub fn folder_prefix(prefix: &str) -> String {
+    let trimmed = prefix.trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", trimmed)
+    }
+}
+
+impl R2Client {
+    // Moves every key under `source_prefix`, the folder marker included. Sources are only
+    // deleted once their copy landed, and copies already at the destination are skipped,
+    // so an interrupted move can simply be run again.
+    pub async fn move_prefix(
+        &self,
+        source_bucket: &str,
+        source_prefix: &str,
+        dest_bucket: &str,
+        dest_prefix: &str,
+        progress: &dyn BatchProgressSink,
+    ) -> Result<PrefixMoveResult, R2Error> {
+        let source_prefix = folder_prefix(source_prefix);
+        let dest_prefix = folder_prefix(dest_prefix);
+        if source_prefix.is_empty() {
+            return Err(R2Error::InvalidInput(t("prefix.empty").to_string()));
+        }
+        if source_bucket == dest_bucket {
+            if source_prefix == dest_prefix {
+                return Ok(PrefixMoveResult::default());
+            }
+            if dest_prefix.starts_with(&source_prefix) {
+                return Err(R2Error::InvalidInput(t("prefix.into_itself").to_string()));
+            }
+        }
+
+        let sources = self
+            .list_objects_recursive(source_bucket, &source_prefix)
+            .await?;
+        let existing: HashMap<String, (i64, Option<String>)> = self
+            .list_objects_recursive(dest_bucket, &dest_prefix)
+            .await?
+            .into_iter()
+            .map(|object| (object.key, (object.size, object.etag)))
+            .collect();
+
+        progress.start_phase(BatchPhase::Copy, sources.len() as u64);
+
+        let (source_prefix, dest_prefix, existing) = (&source_prefix, &dest_prefix, &existing);
+        // Built up front so the stream holds plain futures rather than a borrowing closure
+        let copies: Vec<_> = sources
+            .iter()
+            .map(|object| async move {
+                let dest_key = format!("{}{}", dest_prefix, &object.key[source_prefix.len()..]);
+                let already_copied = existing.get(&dest_key).is_some_and(|(size, etag)| {
+                    *size == object.size && etag.is_some() && *etag == object.etag
+                });
+                let result = if already_copied {
+                    Ok(())
+                } else {
+                    self.copy_object(source_bucket, &object.key, dest_bucket, &dest_key, None)
+                        .await
+                };
+                progress.advance(1);
+                (object.key.clone(), result)
+            })
+            .collect();
+        let copies: Vec<(String, Result<(), R2Error>)> = stream::iter(copies)
+            .buffer_unordered(MAX_CONCURRENT_COPIES)
+            .collect()
+            .await;
+
+        let mut failed = Vec::new();
+        let mut copied = Vec::new();
+        for (key, result) in copies {
+            match result {
+                Ok(()) => copied.push(key),
+                Err(error) => failed.push(KeyError { key, error }),
+            }
+        }
+
+        // Keep the source folder visible while anything is still left in it
+        if !failed.is_empty() {
+            copied.retain(|key| key != source_prefix);
+        }
+
+        progress.start_phase(BatchPhase::Delete, copied.len() as u64);
+
+        let mut moved = 0;
+        for batch in copied.chunks(DELETE_BATCH_SIZE) {
+            match self.delete_objects(source_bucket, batch).await {
+                Ok(result) => {
+                    moved += result.deleted.len() as u64;
+                    failed.extend(result.failed);
+                }
+                Err(error) => failed.extend(batch.iter().map(|key| KeyError {
+                    key: key.clone(),
+                    error: error.clone(),
+                })),
+            }
+            progress.advance(batch.len() as u64);
+        }
+
+        Ok(PrefixMoveResult { moved, failed })
+    }
+
+    // Deletes a folder: its marker and every key beneath it
+    pub async fn delete_prefix(
+        &self,
+        bucket_name: &str,
+        prefix: &str,
+        progress: &dyn BatchProgressSink,
+    ) -> Result<PrefixDeleteResult, R2Error> {
+        let prefix = folder_prefix(prefix);
+        if prefix.is_empty() {
+            return Err(R2Error::InvalidInput(t("prefix.empty").to_string()));
+        }
+        self.delete_all_under(bucket_name, &prefix, progress).await
+    }
+
+    // Clears out a bucket so it can be deleted. Unfinished multipart uploads count as
+    // content too, so they are aborted.
+    pub async fn empty_bucket(
+        &self,
+        bucket_name: &str,
+        progress: &dyn BatchProgressSink,
+    ) -> Result<PrefixDeleteResult, R2Error> {
+        let result = self.delete_all_under(bucket_name, "", progress).await?;
+        self.abort_incomplete_uploads(bucket_name).await?;
+        Ok(result)
+    }
+
+    async fn delete_all_under(
+        &self,
+        bucket_name: &str,
+        prefix: &str,
+        progress: &dyn BatchProgressSink,
+    ) -> Result<PrefixDeleteResult, R2Error> {
+        let keys: Vec<String> = self
+            .list_objects_recursive(bucket_name, prefix)
+            .await?
+            .into_iter()
+            .map(|object| object.key)
+            .collect();
+
+        progress.start_phase(BatchPhase::Delete, keys.len() as u64);
+
+        // A batch refused as a whole fails each of its keys, so the rest still go
+        let mut result = PrefixDeleteResult::default();
+        for batch in keys.chunks(DELETE_BATCH_SIZE) {
+            match self.delete_objects(bucket_name, batch).await {
+                Ok(batch_result) => {
+                    result.deleted += batch_result.deleted.len() as u64;
+                    result.failed.extend(batch_result.failed);
+                }
+                Err(error) => result.failed.extend(batch.iter().map(|key| KeyError {
+                    key: key.clone(),
+                    error: error.clone(),
+                })),
+            }
+            progress.advance(batch.len() as u64);
+        }
+
+        Ok(result)
+    }
+
+    async fn abort_incomplete_uploads(&self, bucket_name: &str) -> Result<(), R2Error> {
+        let (mut key_marker, mut upload_id_marker) = (None, None);
+
+        loop {
+            let response = self
+                .client
+                .list_multipart_uploads()
+                .bucket(bucket_name)
+                .set_key_marker(key_marker.take())
+                .set_upload_id_marker(upload_id_marker.take())
+                .send()
+                .await
+                .map_err(R2Error::from_sdk)?;
+
+            for upload in response.uploads() {
+                if let (Some(key), Some(upload_id)) = (upload.key(), upload.upload_id()) {
+                    self.abort_multipart_upload(bucket_name, key, upload_id)
+                        .await?;
+                }
+            }
+
+            if !response.is_truncated().unwrap_or(false) {
+                break;
+            }
+            key_marker = response.next_key_marker().map(|m| m.to_string());
+            upload_id_marker = response.next_upload_id_marker().map(|m| m.to_string());
+            if key_marker.is_none() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
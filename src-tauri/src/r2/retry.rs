@@ -0,0 +1,81 @@
+use aws_sdk_s3::config::retry::RetryConfig;
+use aws_sdk_s3::config::timeout::TimeoutConfig;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const BASE_DELAY: Duration = Duration::from_millis(200);
+const MAX_DELAY: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetryMode {
+    Standard,
+    // Standard plus client-side rate limiting once R2 starts throttling
+    Adaptive,
+}
+
+// Timeouts are in seconds; 0 disables one
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub retry_mode: RetryMode,
+    // Counts the first try, so 1 turns retries off
+    pub max_attempts: u32,
+    pub connect_timeout_secs: u64,
+    pub read_timeout_secs: u64,
+    // Whole call including its retries; off by default since a single PUT can take hours
+    pub operation_timeout_secs: u64,
+    pub operation_attempt_timeout_secs: u64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            retry_mode: RetryMode::Standard,
+            max_attempts: 3,
+            connect_timeout_secs: 10,
+            read_timeout_secs: 60,
+            operation_timeout_secs: 0,
+            operation_attempt_timeout_secs: 0,
+        }
+    }
+}
+
+fn secs(value: u64) -> Option<Duration> {
+    (value > 0).then(|| Duration::from_secs(value))
+}
+
+impl NetworkConfig {
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts.max(1)
+    }
+
+    pub fn retry_config(&self) -> RetryConfig {
+        let config = match self.retry_mode {
+            RetryMode::Standard => RetryConfig::standard(),
+            RetryMode::Adaptive => RetryConfig::adaptive(),
+        };
+        config
+            .with_max_attempts(self.max_attempts())
+            .with_initial_backoff(BASE_DELAY)
+            .with_max_backoff(MAX_DELAY)
+    }
+
+    pub fn timeout_config(&self) -> TimeoutConfig {
+        let mut builder = TimeoutConfig::builder();
+        builder
+            .set_connect_timeout(secs(self.connect_timeout_secs))
+            .set_read_timeout(secs(self.read_timeout_secs))
+            .set_operation_timeout(secs(self.operation_timeout_secs))
+            .set_operation_attempt_timeout(secs(self.operation_attempt_timeout_secs));
+        builder.build()
+    }
+}
+
+// Exponential backoff with full jitter, so parallel downloads don't reconnect in lockstep
+pub fn backoff(attempt: u32) -> Duration {
+    let ceiling = BASE_DELAY
+        .saturating_mul(1 << attempt.min(16))
+        .min(MAX_DELAY);
+    ceiling.mul_f64(fastrand::f64())
+}
@@ -1,4 +1,7 @@
+use crate::i18n::t;
+use aws_sdk_s3::primitives::ByteStream;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BucketInfo {
@@ -16,23 +19,271 @@ pub struct ObjectInfo {
     pub etag: Option<String>,
 }
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Jurisdiction {
+    #[default]
+    Default,
+    Eu,
+    Fedramp,
+}
+
+// How to reach an account's storage: an R2 jurisdiction, or any S3-compatible endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EndpointConfig {
+    pub jurisdiction: Jurisdiction,
+    // Replaces the R2 endpoint entirely, e.g. http://localhost:9000 for a local MinIO
+    pub custom_endpoint: Option<String>,
+    pub force_path_style: bool,
+    pub region: Option<String>,
+}
+
+impl Default for EndpointConfig {
+    fn default() -> Self {
+        Self {
+            jurisdiction: Jurisdiction::Default,
+            custom_endpoint: None,
+            force_path_style: true,
+            region: None,
+        }
+    }
+}
+
+impl EndpointConfig {
+    pub fn endpoint_url(&self, account_id: &str) -> String {
+        if let Some(endpoint) = self
+            .custom_endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+        {
+            return endpoint.trim_end_matches('/').to_string();
+        }
+
+        match self.jurisdiction {
+            Jurisdiction::Default => format!("https://{}.r2.cloudflarestorage.com", account_id),
+            Jurisdiction::Eu => format!("https://{}.eu.r2.cloudflarestorage.com", account_id),
+            Jurisdiction::Fedramp => {
+                format!("https://{}.fedramp.r2.cloudflarestorage.com", account_id)
+            }
+        }
+    }
+
+    pub fn region(&self) -> String {
+        self.region
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .unwrap_or("auto")
+            .to_string()
+    }
+}
+
+// Where R2 places a new bucket's data; a hint, not a guarantee
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocationHint {
+    Wnam,
+    Enam,
+    Weur,
+    Eeur,
+    Apac,
+    Oc,
+}
+
+impl LocationHint {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocationHint::Wnam => "wnam",
+            LocationHint::Enam => "enam",
+            LocationHint::Weur => "weur",
+            LocationHint::Eeur => "eeur",
+            LocationHint::Apac => "apac",
+            LocationHint::Oc => "oc",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CreateBucketOptions {
+    // None leaves placement to R2
+    pub location_hint: Option<LocationHint>,
+    // None uses the account's own jurisdiction
+    pub jurisdiction: Option<Jurisdiction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectPage {
+    pub objects: Vec<ObjectInfo>,
+    pub continuation_token: Option<String>,
+    pub has_more: bool,
+}
+
+// Headers and user metadata a copy carries over to the new object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectMetadata {
+    pub key: String,
+    pub size: u64,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content_type: Option<String>,
+    pub cache_control: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_encoding: Option<String>,
+    pub metadata: HashMap<String, String>,
+}
+
+// Fields left as None keep the source object's value; an empty string removes a header
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetadataReplacement {
+    pub content_type: Option<String>,
+    pub cache_control: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_encoding: Option<String>,
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadedPart {
+    pub part_number: i32,
+    pub etag: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PartInfo {
+    pub part_number: i32,
+    pub etag: String,
+    pub size: u64,
+}
+
+pub struct ObjectStream {
+    pub body: ByteStream,
+    pub content_length: u64,
+    pub etag: Option<String>,
+}
+
+pub trait ProgressSink: Send + Sync {
+    fn set_total(&self, total_bytes: u64);
+    fn advance(&self, bytes: u64);
+    // The bytes counted so far were thrown away and the transfer starts over
+    fn restart(&self);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchPhase {
+    Copy,
+    Delete,
+}
+
+// Progress of an operation over many keys, counted in objects rather than bytes
+pub trait BatchProgressSink: Send + Sync {
+    fn start_phase(&self, phase: BatchPhase, total: u64);
+    fn advance(&self, items: u64);
+}
+
+// A key a batch operation couldn't process, and why
+#[derive(Debug, Serialize)]
+pub struct KeyError {
+    pub key: String,
+    pub error: R2Error,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DeleteResult {
+    pub deleted: Vec<String>,
+    pub failed: Vec<KeyError>,
+}
+
+impl DeleteResult {
+    // Folds in another batch's outcome
+    pub fn merge(&mut self, other: DeleteResult) {
+        self.deleted.extend(other.deleted);
+        self.failed.extend(other.failed);
+    }
+}
+
+pub trait CheckpointSink: Send + Sync {
+    fn multipart_started(&self, upload_id: &str, part_size: u64);
+    fn part_uploaded(&self, part: &UploadedPart);
+    fn download_started(&self, etag: Option<&str>, total_bytes: u64);
+    fn download_checkpoint(&self, bytes_written: u64);
+}
+
+// What the service reported about a failed request, kept for the frontend
+#[derive(Debug, Clone, Default)]
+pub struct ErrorDetail {
+    pub message: String,
+    pub http_status: Option<u16>,
+    pub s3_code: Option<String>,
+    pub request_id: Option<String>,
+}
+
+impl std::fmt::Display for ErrorDetail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for ErrorDetail {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&str> for ErrorDetail {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum R2Error {
-    #[error("AWS SDK 错误: {0}")]
-    SdkError(String),
+    #[error("{}: {}", t("error.sdk_error"), .0)]
+    SdkError(ErrorDetail),
+
+    #[error("{}: {}", t("error.credentials_error"), .0)]
+    CredentialsError(ErrorDetail),
+
+    #[error("{}: {}", t("error.bucket_not_found"), .0)]
+    BucketNotFound(ErrorDetail),
+
+    #[error("{}: {}", t("error.object_not_found"), .0)]
+    ObjectNotFound(ErrorDetail),
+
+    #[error("{}: {}", t("error.network_error"), .0)]
+    NetworkError(ErrorDetail),
+
+    #[error("{}: {}", t("error.precondition_failed"), .0)]
+    PreconditionFailed(ErrorDetail),
+
+    #[error("{}: {}", t("error.io_error"), .0)]
+    IoError(String),
+
+    #[error("{}: {}", t("error.integrity_error"), .0)]
+    IntegrityError(String),
+
+    #[error("{}: {}", t("error.invalid_input"), .0)]
+    InvalidInput(String),
 
-    #[error("凭证错误: {0}")]
-    CredentialsError(String),
+    #[error("{}: {}", t("error.config_error"), .0)]
+    ConfigError(String),
 
-    #[error("存储桶不存在: {0}")]
-    BucketNotFound(String),
+    #[error("{}", t("error.vault_locked"))]
+    VaultLocked,
 
-    #[error("对象不存在: {0}")]
-    ObjectNotFound(String),
+    #[error("{}", t("error.transfer_not_found"))]
+    TransferNotFound,
 
-    #[error("网络错误: {0}")]
-    NetworkError(String),
+    #[error("{}", t("error.cancelled"))]
+    Cancelled,
 
-    #[error("未知错误: {0}")]
+    #[error("{}: {}", t("error.unknown"), .0)]
     Unknown(String),
 }
@@ -0,0 +1,243 @@
+use super::client::R2Client;
+use super::control::TransferHooks;
+use super::headers::{read_head, UploadHeaders};
+use super::types::{PartInfo, R2Error, UploadedPart};
+use futures_util::stream::{self, StreamExt};
+use std::collections::HashSet;
+use std::path::Path;
+
+const MIB: u64 = 1024 * 1024;
+
+// R2 multipart limits
+pub const MIN_PART_SIZE: u64 = 5 * MIB;
+pub const MAX_PART_SIZE: u64 = 5 * 1024 * MIB;
+pub const MAX_PARTS: u64 = 10_000;
+pub const MAX_SINGLE_PUT_SIZE: u64 = 5 * 1024 * MIB - 5 * MIB;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MultipartConfig {
+    pub part_size: u64,
+    pub threshold: u64,
+    pub max_concurrent_parts: usize,
+}
+
+impl Default for MultipartConfig {
+    fn default() -> Self {
+        Self {
+            part_size: 8 * MIB,
+            threshold: 64 * MIB,
+            max_concurrent_parts: 4,
+        }
+    }
+}
+
+impl MultipartConfig {
+    pub fn use_multipart(&self, file_size: u64) -> bool {
+        file_size > self.threshold.min(MAX_SINGLE_PUT_SIZE)
+    }
+
+    // Grow the part size when the file would otherwise need more than MAX_PARTS parts
+    pub fn part_size_for(&self, file_size: u64) -> u64 {
+        let min_for_file = file_size.div_ceil(MAX_PARTS);
+        self.part_size
+            .max(min_for_file)
+            .clamp(MIN_PART_SIZE, MAX_PART_SIZE)
+    }
+}
+
+pub struct MultipartResume {
+    pub upload_id: String,
+    pub part_size: u64,
+}
+
+// Reuse every part R2 already holds in full; missing or short parts are sent again
+fn reusable_parts(listed: Vec<PartInfo>, part_size: u64, file_size: u64) -> Vec<UploadedPart> {
+    let part_count = file_size.div_ceil(part_size);
+
+    listed
+        .into_iter()
+        .filter(|part| {
+            let number = part.part_number as u64;
+            if part.part_number < 1 || number > part_count {
+                return false;
+            }
+            let offset = (number - 1) * part_size;
+            part.size == part_size.min(file_size - offset)
+        })
+        .map(|part| UploadedPart {
+            part_number: part.part_number,
+            etag: part.etag,
+        })
+        .collect()
+}
+
+impl R2Client {
+    // `headers` come resolved from the caller; a missing Content-Type is detected here
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upload_file(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        path: &Path,
+        headers: UploadHeaders,
+        config: &MultipartConfig,
+        resume: Option<MultipartResume>,
+        hooks: &TransferHooks<'_>,
+    ) -> Result<(), R2Error> {
+        let file_size = tokio::fs::metadata(path)
+            .await
+            .map_err(|e| R2Error::IoError(e.to_string()))?
+            .len();
+
+        hooks.progress.set_total(file_size);
+        hooks.control.checkpoint().await?;
+
+        let headers = headers.with_detected_type(key, &read_head(path).await);
+
+        if !config.use_multipart(file_size) {
+            tokio::select! {
+                result = self.put_object_from_path(bucket_name, key, path, &headers) => result?,
+                _ = hooks.control.cancelled() => return Err(R2Error::Cancelled),
+            }
+            hooks.progress.advance(file_size);
+            return Ok(());
+        }
+
+        let (upload_id, part_size, parts) = match self
+            .resume_multipart(bucket_name, key, file_size, resume)
+            .await?
+        {
+            Some(resumed) => resumed,
+            None => {
+                let upload_id = self
+                    .create_multipart_upload(bucket_name, key, &headers)
+                    .await?;
+                let part_size = config.part_size_for(file_size);
+                if let Some(checkpoint) = hooks.checkpoint {
+                    checkpoint.multipart_started(&upload_id, part_size);
+                }
+                (upload_id, part_size, Vec::new())
+            }
+        };
+
+        let result = match self
+            .upload_parts(
+                bucket_name,
+                key,
+                &upload_id,
+                path,
+                file_size,
+                part_size,
+                config.max_concurrent_parts,
+                parts,
+                hooks,
+            )
+            .await
+        {
+            Ok(parts) => {
+                self.complete_multipart_upload(bucket_name, key, &upload_id, &parts)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+
+        // Don't leave orphaned parts behind on R2, unless the journal keeps them for a resume.
+        // Only a failure a retry could get past is worth resuming; network errors count.
+        let resumable =
+            hooks.checkpoint.is_some() && result.as_ref().is_err_and(|e| e.is_retryable());
+        if result.is_err() && !resumable {
+            let _ = self
+                .abort_multipart_upload(bucket_name, key, &upload_id)
+                .await;
+        }
+
+        result
+    }
+
+    async fn resume_multipart(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        file_size: u64,
+        resume: Option<MultipartResume>,
+    ) -> Result<Option<(String, u64, Vec<UploadedPart>)>, R2Error> {
+        let Some(resume) = resume else {
+            return Ok(None);
+        };
+
+        if resume.part_size < MIN_PART_SIZE {
+            let _ = self
+                .abort_multipart_upload(bucket_name, key, &resume.upload_id)
+                .await;
+            return Ok(None);
+        }
+
+        // Ask R2 which parts it actually has rather than trusting the local journal
+        let listed = self.list_parts(bucket_name, key, &resume.upload_id).await?;
+
+        Ok(listed.map(|listed| {
+            let parts = reusable_parts(listed, resume.part_size, file_size);
+            (resume.upload_id, resume.part_size, parts)
+        }))
+    }
+
+    // Parts finished before a pause are kept, so resuming continues with the rest
+    #[allow(clippy::too_many_arguments)]
+    async fn upload_parts(
+        &self,
+        bucket_name: &str,
+        key: &str,
+        upload_id: &str,
+        path: &Path,
+        file_size: u64,
+        part_size: u64,
+        max_concurrent_parts: usize,
+        mut parts: Vec<UploadedPart>,
+        hooks: &TransferHooks<'_>,
+    ) -> Result<Vec<UploadedPart>, R2Error> {
+        let done: HashSet<i32> = parts.iter().map(|p| p.part_number).collect();
+        let pending: Vec<(i32, u64, u64)> = (1..=file_size.div_ceil(part_size))
+            .filter(|n| !done.contains(&(*n as i32)))
+            .map(|n| {
+                let offset = (n - 1) * part_size;
+                (n as i32, offset, part_size.min(file_size - offset))
+            })
+            .collect();
+
+        let remaining: u64 = pending.iter().map(|(_, _, length)| length).sum();
+        hooks.progress.advance(file_size - remaining);
+
+        let mut uploads = stream::iter(pending)
+            .map(|(part_number, offset, length)| async move {
+                hooks.control.checkpoint().await?;
+                // The SDK retries each part on its own, re-reading its range of the file, so
+                // one dropped connection doesn't fail the whole upload
+                let upload = self.upload_part(
+                    bucket_name,
+                    key,
+                    upload_id,
+                    part_number,
+                    path,
+                    offset,
+                    length,
+                );
+                tokio::select! {
+                    part = upload => part.map(|part| (part, length)),
+                    _ = hooks.control.cancelled() => Err(R2Error::Cancelled),
+                }
+            })
+            .buffer_unordered(max_concurrent_parts.max(1));
+
+        while let Some(result) = uploads.next().await {
+            let (part, length) = result?;
+            if let Some(checkpoint) = hooks.checkpoint {
+                checkpoint.part_uploaded(&part);
+            }
+            hooks.progress.advance(length);
+            parts.push(part);
+        }
+
+        parts.sort_by_key(|p| p.part_number);
+        Ok(parts)
+    }
+}
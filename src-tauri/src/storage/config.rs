@@ -1,3 +1,7 @@
+use super::secrets::{preferred_backend, store_for, SecretBackendKind, SecretRef};
+use super::settings::AppSettings;
+use crate::i18n::t;
+use crate::r2::types::EndpointConfig;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -12,11 +16,18 @@ pub struct Account {
     pub access_key_id: String,
     #[serde(skip_serializing, default)]
     pub secret_access_key: String,
+    #[serde(default)]
+    pub endpoint: EndpointConfig,
+    // Set when the secret came out of the vault, so clients built from it go when it locks
+    #[serde(skip)]
+    pub secret_in_vault: bool,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 struct Config {
     accounts: Vec<AccountEntry>,
+    #[serde(default)]
+    settings: AppSettings,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,35 +36,53 @@ struct AccountEntry {
     name: String,
     account_id: String,
     access_key_id: String,
-    #[serde(default)]
+    // Base64 secret written by older versions; moved into the secret store on load
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     secret_key_encoded: String,
+    #[serde(default)]
+    secret_ref: Option<SecretRef>,
+    #[serde(default)]
+    endpoint: EndpointConfig,
 }
 
 #[derive(Debug, Error)]
 pub enum ConfigError {
-    #[error("IO 错误: {0}")]
+    #[error("{}: {}", t("config.io"), .0)]
     Io(#[from] std::io::Error),
 
-    #[error("序列化错误: {0}")]
+    #[error("{}: {}", t("config.serialization"), .0)]
     Serialization(#[from] serde_json::Error),
 
-    #[error("配置目录错误")]
+    #[error("{}", t("config.dir"))]
     ConfigDir,
+
+    #[error("{}: {}", t("config.secret"), .0)]
+    Secret(String),
+
+    #[error("{}", t("error.vault_locked"))]
+    VaultLocked,
+
+    #[error("{}", t("config.vault_password"))]
+    VaultPassword,
 }
 
 pub struct ConfigStore {
     config_path: PathBuf,
 }
 
-impl ConfigStore {
-    pub fn new() -> Result<Self, ConfigError> {
-        let config_dir = dirs::config_dir()
-            .ok_or(ConfigError::ConfigDir)?
-            .join("r2-explorer");
+pub fn app_config_dir() -> Result<PathBuf, ConfigError> {
+    let config_dir = dirs::config_dir()
+        .ok_or(ConfigError::ConfigDir)?
+        .join("r2-explorer");
+
+    fs::create_dir_all(&config_dir)?;
 
-        fs::create_dir_all(&config_dir)?;
+    Ok(config_dir)
+}
 
-        let config_path = config_dir.join("config.json");
+impl ConfigStore {
+    pub fn new() -> Result<Self, ConfigError> {
+        let config_path = app_config_dir()?.join("config.json");
 
         Ok(Self { config_path })
     }
@@ -64,20 +93,65 @@ impl ConfigStore {
         }
 
         let content = fs::read_to_string(&self.config_path)?;
-        let config: Config = serde_json::from_str(&content)?;
+        let mut config: Config = serde_json::from_str(&content)?;
+
+        if Self::migrate_secrets(&mut config) {
+            self.save_config(&config)?;
+        }
+
         Ok(config)
     }
 
+    // Move legacy base64 secrets out of config.json. An entry that can't be moved keeps
+    // its encoded secret and is retried on the next load.
+    fn migrate_secrets(config: &mut Config) -> bool {
+        let backend = preferred_backend(config.settings.security.secret_backend);
+        let mut migrated = false;
+
+        for entry in config
+            .accounts
+            .iter_mut()
+            .filter(|e| !e.secret_key_encoded.is_empty())
+        {
+            let secret = Self::decode_secret(&entry.secret_key_encoded);
+            if let Ok(secret_ref) = Self::store_secret(backend, &entry.id, &secret) {
+                entry.secret_ref = Some(secret_ref);
+                entry.secret_key_encoded.clear();
+                migrated = true;
+            }
+        }
+
+        migrated
+    }
+
+    fn store_secret(
+        backend: SecretBackendKind,
+        id: &str,
+        secret: &str,
+    ) -> Result<SecretRef, ConfigError> {
+        let secret_ref = SecretRef {
+            backend,
+            name: format!("account:{}", id),
+        };
+        store_for(secret_ref.backend).set(&secret_ref.name, secret)?;
+        Ok(secret_ref)
+    }
+
+    fn load_secret(entry: &AccountEntry) -> Result<String, ConfigError> {
+        match &entry.secret_ref {
+            Some(secret_ref) => Ok(store_for(secret_ref.backend)
+                .get(&secret_ref.name)?
+                .unwrap_or_default()),
+            None => Ok(Self::decode_secret(&entry.secret_key_encoded)),
+        }
+    }
+
     fn save_config(&self, config: &Config) -> Result<(), ConfigError> {
         let content = serde_json::to_string_pretty(config)?;
         fs::write(&self.config_path, content)?;
         Ok(())
     }
 
-    fn encode_secret(secret: &str) -> String {
-        BASE64.encode(secret.as_bytes())
-    }
-
     fn decode_secret(encoded: &str) -> String {
         BASE64
             .decode(encoded)
@@ -89,15 +163,27 @@ impl ConfigStore {
     pub fn save_account(&self, account: &Account) -> Result<(), ConfigError> {
         let mut config = self.load_config()?;
 
+        let backend = preferred_backend(config.settings.security.secret_backend);
+        let secret_ref = Self::store_secret(backend, &account.id, &account.secret_access_key)?;
         let entry = AccountEntry {
             id: account.id.clone(),
             name: account.name.clone(),
             account_id: account.account_id.clone(),
             access_key_id: account.access_key_id.clone(),
-            secret_key_encoded: Self::encode_secret(&account.secret_access_key),
+            secret_key_encoded: String::new(),
+            secret_ref: Some(secret_ref.clone()),
+            endpoint: account.endpoint.clone(),
         };
 
         if let Some(existing) = config.accounts.iter_mut().find(|a| a.id == account.id) {
+            // The preferred backend may have changed since the secret was first stored
+            if let Some(old) = existing
+                .secret_ref
+                .as_ref()
+                .filter(|old| **old != secret_ref)
+            {
+                let _ = store_for(old.backend).delete(&old.name);
+            }
             *existing = entry;
         } else {
             config.accounts.push(entry);
@@ -106,6 +192,7 @@ impl ConfigStore {
         self.save_config(&config)
     }
 
+    // Listing leaves secrets empty so it doesn't hit the secret store once per account
     pub fn get_accounts(&self) -> Result<Vec<Account>, ConfigError> {
         let config = self.load_config()?;
 
@@ -117,7 +204,9 @@ impl ConfigStore {
                 name: entry.name,
                 account_id: entry.account_id,
                 access_key_id: entry.access_key_id,
-                secret_access_key: Self::decode_secret(&entry.secret_key_encoded),
+                secret_access_key: String::new(),
+                endpoint: entry.endpoint,
+                secret_in_vault: false,
             })
             .collect();
 
@@ -125,13 +214,66 @@ impl ConfigStore {
     }
 
     pub fn get_account(&self, id: &str) -> Result<Option<Account>, ConfigError> {
-        let accounts = self.get_accounts()?;
-        Ok(accounts.into_iter().find(|a| a.id == id))
+        let config = self.load_config()?;
+        let Some(entry) = config.accounts.into_iter().find(|a| a.id == id) else {
+            return Ok(None);
+        };
+
+        Ok(Some(Account {
+            secret_access_key: Self::load_secret(&entry)?,
+            secret_in_vault: entry
+                .secret_ref
+                .as_ref()
+                .is_some_and(|r| r.backend == SecretBackendKind::Vault),
+            id: entry.id,
+            name: entry.name,
+            account_id: entry.account_id,
+            access_key_id: entry.access_key_id,
+            endpoint: entry.endpoint,
+        }))
     }
 
     pub fn delete_account(&self, id: &str) -> Result<(), ConfigError> {
         let mut config = self.load_config()?;
+
+        if let Some(secret_ref) = config
+            .accounts
+            .iter()
+            .find(|a| a.id == id)
+            .and_then(|a| a.secret_ref.as_ref())
+        {
+            store_for(secret_ref.backend).delete(&secret_ref.name)?;
+        }
+
         config.accounts.retain(|a| a.id != id);
         self.save_config(&config)
     }
+
+    pub fn get_settings(&self) -> Result<AppSettings, ConfigError> {
+        Ok(self.load_config()?.settings)
+    }
+
+    pub fn save_settings(&self, settings: &AppSettings) -> Result<(), ConfigError> {
+        let mut config = self.load_config()?;
+        let backend = preferred_backend(settings.security.secret_backend);
+        config.settings = settings.clone();
+
+        // Switching backends moves every stored secret. Each move is saved as it happens,
+        // so a failure part-way (e.g. a locked vault) leaves every ref valid and saving
+        // the settings again picks up where it stopped.
+        for index in 0..config.accounts.len() {
+            let entry = &config.accounts[index];
+            let Some(old) = entry.secret_ref.clone().filter(|r| r.backend != backend) else {
+                continue;
+            };
+
+            let secret = store_for(old.backend).get(&old.name)?.unwrap_or_default();
+            let moved = Self::store_secret(backend, &entry.id, &secret)?;
+            config.accounts[index].secret_ref = Some(moved);
+            self.save_config(&config)?;
+            let _ = store_for(old.backend).delete(&old.name);
+        }
+
+        self.save_config(&config)
+    }
 }
@@ -0,0 +1,184 @@
+use super::config::{app_config_dir, ConfigError};
+use crate::r2::headers::UploadHeaders;
+use crate::r2::types::UploadedPart;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferKind {
+    Upload,
+    Download,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: String,
+    pub kind: TransferKind,
+    pub account_id: String,
+    pub bucket_name: String,
+    pub key: String,
+    pub local_path: String,
+    #[serde(default)]
+    pub total_bytes: u64,
+    // Size and mtime of the source file, used to detect edits before resuming an upload
+    #[serde(default)]
+    pub local_modified: Option<i64>,
+    // Per-upload headers as requested; settings rules are applied when the upload starts
+    #[serde(default)]
+    pub headers: UploadHeaders,
+    #[serde(default)]
+    pub upload_id: Option<String>,
+    #[serde(default)]
+    pub part_size: u64,
+    #[serde(default)]
+    pub completed_parts: Vec<UploadedPart>,
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub bytes_transferred: u64,
+    pub created_at: String,
+}
+
+struct JournalState {
+    entries: Vec<JournalEntry>,
+    // Bumped on every change, so an older snapshot never overwrites a newer one
+    version: u64,
+    flush_requested: bool,
+}
+
+struct Shared {
+    journal_path: PathBuf,
+    state: Mutex<JournalState>,
+    flush: Condvar,
+    // Version on disk; held while writing so snapshots land in order
+    written: Mutex<u64>,
+}
+
+// Adding and removing transfers is written through at once. Progress updates, which come
+// once per part or checkpoint, are coalesced and written by a background thread, so the
+// file may trail the transfer slightly; resuming from an older offset is always safe.
+pub struct TransferJournal {
+    shared: Arc<Shared>,
+}
+
+impl TransferJournal {
+    pub fn open() -> Result<Self, ConfigError> {
+        let journal_path = app_config_dir()?.join("transfers.json");
+
+        // A corrupt journal only costs the ability to resume, so start over instead of failing
+        let entries = fs::read_to_string(&journal_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let shared = Arc::new(Shared {
+            journal_path,
+            state: Mutex::new(JournalState {
+                entries,
+                version: 0,
+                flush_requested: false,
+            }),
+            flush: Condvar::new(),
+            written: Mutex::new(0),
+        });
+
+        let writer = shared.clone();
+        thread::Builder::new()
+            .name("transfer-journal".into())
+            .spawn(move || writer.write_loop())?;
+
+        Ok(Self { shared })
+    }
+
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        self.shared.state.lock().unwrap().entries.clone()
+    }
+
+    pub fn get(&self, id: &str) -> Option<JournalEntry> {
+        self.shared
+            .state
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .find(|e| e.id == id)
+            .cloned()
+    }
+
+    pub fn insert(&self, entry: JournalEntry) -> Result<(), ConfigError> {
+        let snapshot = self.shared.change(|entries| {
+            entries.retain(|e| e.id != entry.id);
+            entries.push(entry);
+        });
+        self.shared.persist(snapshot)
+    }
+
+    pub fn update(
+        &self,
+        id: &str,
+        apply: impl FnOnce(&mut JournalEntry),
+    ) -> Result<(), ConfigError> {
+        let mut state = self.shared.state.lock().unwrap();
+        match state.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => apply(entry),
+            None => return Ok(()),
+        }
+        state.version += 1;
+        state.flush_requested = true;
+        self.shared.flush.notify_one();
+        Ok(())
+    }
+
+    pub fn remove(&self, id: &str) -> Result<(), ConfigError> {
+        let snapshot = self.shared.change(|entries| entries.retain(|e| e.id != id));
+        self.shared.persist(snapshot)
+    }
+}
+
+impl Shared {
+    fn change(&self, apply: impl FnOnce(&mut Vec<JournalEntry>)) -> (u64, Vec<JournalEntry>) {
+        let mut state = self.state.lock().unwrap();
+        apply(&mut state.entries);
+        state.version += 1;
+        (state.version, state.entries.clone())
+    }
+
+    fn write_loop(&self) {
+        loop {
+            let snapshot = {
+                let mut state = self
+                    .flush
+                    .wait_while(self.state.lock().unwrap(), |s| !s.flush_requested)
+                    .unwrap();
+                state.flush_requested = false;
+                (state.version, state.entries.clone())
+            };
+            // A failed write is picked up by the next one
+            let _ = self.persist(snapshot);
+        }
+    }
+
+    // Write and fsync a sibling file, then rename it over the journal, so a crash leaves
+    // either the old journal or the new one
+    fn persist(&self, (version, entries): (u64, Vec<JournalEntry>)) -> Result<(), ConfigError> {
+        let mut written = self.written.lock().unwrap();
+        if version <= *written {
+            return Ok(());
+        }
+
+        let content = serde_json::to_string_pretty(&entries)?;
+        let temp_path = self.journal_path.with_extension("json.tmp");
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, &self.journal_path)?;
+
+        *written = version;
+        Ok(())
+    }
+}
@@ -1 +1,5 @@
 pub mod config;
+pub mod journal;
+pub mod secrets;
+pub mod settings;
+pub mod vault;
@@ -0,0 +1,150 @@
+use super::config::{app_config_dir, ConfigError};
+use super::vault::VaultStore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+const SERVICE: &str = "r2-explorer";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretBackendKind {
+    Keyring,
+    File,
+    Vault,
+}
+
+// Where an account's secret lives; config.json only ever holds this reference
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretRef {
+    pub backend: SecretBackendKind,
+    pub name: String,
+}
+
+pub trait SecretStore: Send + Sync {
+    fn get(&self, name: &str) -> Result<Option<String>, ConfigError>;
+    fn set(&self, name: &str, secret: &str) -> Result<(), ConfigError>;
+    fn delete(&self, name: &str) -> Result<(), ConfigError>;
+}
+
+// Keychain on macOS, Credential Manager on Windows, Secret Service on Linux
+pub struct KeyringStore;
+
+impl KeyringStore {
+    fn entry(name: &str) -> Result<keyring::Entry, ConfigError> {
+        keyring::Entry::new(SERVICE, name).map_err(|e| ConfigError::Secret(e.to_string()))
+    }
+
+    // A missing entry still proves the platform store answered
+    fn is_available() -> bool {
+        matches!(
+            Self::entry("probe").map(|e| e.get_password()),
+            Ok(Ok(_)) | Ok(Err(keyring::Error::NoEntry))
+        )
+    }
+}
+
+impl SecretStore for KeyringStore {
+    fn get(&self, name: &str) -> Result<Option<String>, ConfigError> {
+        match Self::entry(name)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(ConfigError::Secret(e.to_string())),
+        }
+    }
+
+    fn set(&self, name: &str, secret: &str) -> Result<(), ConfigError> {
+        Self::entry(name)?
+            .set_password(secret)
+            .map_err(|e| ConfigError::Secret(e.to_string()))
+    }
+
+    fn delete(&self, name: &str) -> Result<(), ConfigError> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(ConfigError::Secret(e.to_string())),
+        }
+    }
+}
+
+// Fallback for headless Linux without a Secret Service daemon: a JSON file only the
+// current user can read
+pub struct FileStore;
+
+impl FileStore {
+    fn path() -> Result<PathBuf, ConfigError> {
+        Ok(app_config_dir()?.join("secrets.json"))
+    }
+
+    fn load() -> Result<HashMap<String, String>, ConfigError> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(HashMap::new());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    fn save(secrets: &HashMap<String, String>) -> Result<(), ConfigError> {
+        let path = Self::path()?;
+        let temp_path = path.with_extension("json.tmp");
+        let content = serde_json::to_string_pretty(secrets)?;
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        std::io::Write::write_all(&mut options.open(&temp_path)?, content.as_bytes())?;
+        fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+}
+
+impl SecretStore for FileStore {
+    fn get(&self, name: &str) -> Result<Option<String>, ConfigError> {
+        Ok(Self::load()?.remove(name))
+    }
+
+    fn set(&self, name: &str, secret: &str) -> Result<(), ConfigError> {
+        let mut secrets = Self::load()?;
+        secrets.insert(name.to_string(), secret.to_string());
+        Self::save(&secrets)
+    }
+
+    fn delete(&self, name: &str) -> Result<(), ConfigError> {
+        let mut secrets = Self::load()?;
+        if secrets.remove(name).is_some() {
+            Self::save(&secrets)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn store_for(kind: SecretBackendKind) -> &'static dyn SecretStore {
+    match kind {
+        SecretBackendKind::Keyring => &KeyringStore,
+        SecretBackendKind::File => &FileStore,
+        SecretBackendKind::Vault => &VaultStore,
+    }
+}
+
+// The backend chosen in settings, or else the keyring when the platform offers one and
+// the file otherwise; the keyring is probed once per run
+pub fn preferred_backend(configured: Option<SecretBackendKind>) -> SecretBackendKind {
+    static PREFERRED: OnceLock<SecretBackendKind> = OnceLock::new();
+    if let Some(kind) = configured {
+        return kind;
+    }
+    *PREFERRED.get_or_init(|| {
+        if KeyringStore::is_available() {
+            SecretBackendKind::Keyring
+        } else {
+            SecretBackendKind::File
+        }
+    })
+}
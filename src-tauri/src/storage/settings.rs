@@ -0,0 +1,68 @@
+use super::secrets::SecretBackendKind;
+use crate::i18n::Locale;
+use crate::r2::headers::HeaderRule;
+use crate::r2::retry::NetworkConfig;
+use crate::r2::upload::MultipartConfig;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    // None follows the system locale
+    pub locale: Option<Locale>,
+    pub transfer: TransferSettings,
+    // Retry and timeout policy for every R2 client
+    pub network: NetworkConfig,
+    pub security: SecuritySettings,
+    // Headers applied to uploads by key pattern, in order
+    pub header_rules: Vec<HeaderRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransferSettings {
+    pub max_concurrent_transfers: usize,
+    pub max_concurrent_parts: usize,
+    pub part_size: u64,
+    pub multipart_threshold: u64,
+}
+
+impl Default for TransferSettings {
+    fn default() -> Self {
+        let multipart = MultipartConfig::default();
+        Self {
+            max_concurrent_transfers: 3,
+            max_concurrent_parts: multipart.max_concurrent_parts,
+            part_size: multipart.part_size,
+            multipart_threshold: multipart.threshold,
+        }
+    }
+}
+
+impl TransferSettings {
+    pub fn multipart_config(&self) -> MultipartConfig {
+        MultipartConfig {
+            part_size: self.part_size,
+            threshold: self.multipart_threshold,
+            max_concurrent_parts: self.max_concurrent_parts.max(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecuritySettings {
+    // None picks the OS keychain when available and falls back to a file
+    pub secret_backend: Option<SecretBackendKind>,
+    // Idle minutes before the vault locks again; 0 keeps it unlocked for the session
+    pub vault_auto_lock_minutes: u64,
+}
+
+impl Default for SecuritySettings {
+    fn default() -> Self {
+        Self {
+            secret_backend: None,
+            vault_auto_lock_minutes: 15,
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use super::config::{app_config_dir, ConfigError};
+use super::secrets::SecretStore;
+use crate::i18n::t;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+const SALT_LEN: usize = 16;
+// Encrypted with the derived key so a wrong master password is caught on unlock
+const CHECK_PLAINTEXT: &[u8] = b"r2-explorer-vault";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+// Argon2 settings the key was derived with. Stored in the file so a change to the crate's
+// defaults can't lock anyone out of an existing vault.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KdfParams {
+    algorithm: String,
+    version: u32,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    output_len: usize,
+}
+
+impl Default for KdfParams {
+    // argon2 0.5's defaults, which vaults written before the parameters were stored used
+    fn default() -> Self {
+        Self {
+            algorithm: "argon2id".to_string(),
+            version: 0x13,
+            m_cost: 19 * 1024,
+            t_cost: 2,
+            p_cost: 1,
+            output_len: 32,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VaultFile {
+    salt: String,
+    #[serde(default)]
+    kdf: KdfParams,
+    check: Sealed,
+    #[serde(default)]
+    entries: HashMap<String, Sealed>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct VaultStatus {
+    pub initialized: bool,
+    pub unlocked: bool,
+}
+
+struct Session {
+    key: Zeroizing<[u8; 32]>,
+    last_used: Instant,
+}
+
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+static AUTO_LOCK_SECS: AtomicU64 = AtomicU64::new(15 * 60);
+// Bumped every time a session ends, so holders of vault-derived state can tell it went stale
+static LOCK_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn vault_path() -> Result<PathBuf, ConfigError> {
+    Ok(app_config_dir()?.join("vault.json"))
+}
+
+fn load() -> Result<Option<VaultFile>, ConfigError> {
+    let path = vault_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+}
+
+fn save(vault: &VaultFile) -> Result<(), ConfigError> {
+    let path = vault_path()?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, serde_json::to_string_pretty(vault)?)?;
+    fs::rename(&temp_path, &path)?;
+    Ok(())
+}
+
+fn derive_key(
+    password: &str,
+    salt: &[u8],
+    kdf: &KdfParams,
+) -> Result<Zeroizing<[u8; 32]>, ConfigError> {
+    let secret_err = |e: argon2::Error| ConfigError::Secret(e.to_string());
+    if kdf.output_len != 32 {
+        return Err(ConfigError::Secret(t("vault.bad_kdf").to_string()));
+    }
+
+    let algorithm = Algorithm::new(&kdf.algorithm).map_err(secret_err)?;
+    let version = Version::try_from(kdf.version).map_err(secret_err)?;
+    let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(kdf.output_len))
+        .map_err(secret_err)?;
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(algorithm, version, params)
+        .hash_password_into(password.as_bytes(), salt, key.as_mut())
+        .map_err(secret_err)?;
+    Ok(key)
+}
+
+fn seal(key: &[u8; 32], plaintext: &[u8]) -> Result<Sealed, ConfigError> {
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| ConfigError::Secret(t("vault.encrypt_failed").to_string()))?;
+
+    Ok(Sealed {
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn open(key: &[u8; 32], sealed: &Sealed) -> Option<Zeroizing<Vec<u8>>> {
+    let nonce = BASE64.decode(&sealed.nonce).ok()?;
+    let ciphertext = BASE64.decode(&sealed.ciphertext).ok()?;
+    if nonce.len() != 24 {
+        return None;
+    }
+
+    XChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+        .ok()
+        .map(Zeroizing::new)
+}
+
+// Hands out the session key, locking first if the vault sat idle past the timeout
+fn session_key() -> Result<Zeroizing<[u8; 32]>, ConfigError> {
+    expire_idle();
+    let mut session = SESSION.lock().unwrap();
+    let session = session.as_mut().ok_or(ConfigError::VaultLocked)?;
+    session.last_used = Instant::now();
+    Ok(session.key.clone())
+}
+
+pub fn status() -> Result<VaultStatus, ConfigError> {
+    expire_idle();
+    Ok(VaultStatus {
+        initialized: vault_path()?.exists(),
+        unlocked: SESSION.lock().unwrap().is_some(),
+    })
+}
+
+// Sets up a new, empty vault protected by `password` and leaves it unlocked
+pub fn create(password: &str) -> Result<(), ConfigError> {
+    if load()?.is_some() {
+        return Err(ConfigError::Secret(t("vault.exists").to_string()));
+    }
+    if password.is_empty() {
+        return Err(ConfigError::Secret(t("vault.empty_password").to_string()));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let kdf = KdfParams::default();
+    let key = derive_key(password, &salt, &kdf)?;
+
+    save(&VaultFile {
+        salt: BASE64.encode(salt),
+        kdf,
+        check: seal(&key, CHECK_PLAINTEXT)?,
+        entries: HashMap::new(),
+    })?;
+
+    *SESSION.lock().unwrap() = Some(Session {
+        key,
+        last_used: Instant::now(),
+    });
+    Ok(())
+}
+
+pub fn unlock(password: &str) -> Result<(), ConfigError> {
+    let vault = load()?.ok_or_else(|| ConfigError::Secret(t("vault.not_created").to_string()))?;
+    let salt = BASE64
+        .decode(&vault.salt)
+        .map_err(|e| ConfigError::Secret(e.to_string()))?;
+    let key = derive_key(password, &salt, &vault.kdf)?;
+
+    match open(&key, &vault.check) {
+        Some(check) if check.as_slice() == CHECK_PLAINTEXT => {}
+        _ => return Err(ConfigError::VaultPassword),
+    }
+
+    *SESSION.lock().unwrap() = Some(Session {
+        key,
+        last_used: Instant::now(),
+    });
+    Ok(())
+}
+
+pub fn lock() {
+    end_session(&mut SESSION.lock().unwrap());
+}
+
+fn end_session(session: &mut Option<Session>) {
+    if session.take().is_some() {
+        LOCK_GENERATION.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+pub fn generation() -> u64 {
+    LOCK_GENERATION.load(Ordering::SeqCst)
+}
+
+// Counts as vault activity for state derived from the session of `generation`. Returns
+// false once that session has been locked, in which case the state must be dropped.
+pub fn touch(generation: u64) -> bool {
+    expire_idle();
+    let mut session = SESSION.lock().unwrap();
+    match session.as_mut() {
+        Some(session) if LOCK_GENERATION.load(Ordering::SeqCst) == generation => {
+            session.last_used = Instant::now();
+            true
+        }
+        _ => false,
+    }
+}
+
+pub fn set_auto_lock(minutes: u64) {
+    AUTO_LOCK_SECS.store(minutes.saturating_mul(60), Ordering::Relaxed);
+}
+
+// Locks the vault once it has been idle longer than the auto-lock timeout (0 disables it).
+// Returns true if this call locked it.
+pub fn expire_idle() -> bool {
+    let timeout = AUTO_LOCK_SECS.load(Ordering::Relaxed);
+    let mut session = SESSION.lock().unwrap();
+    let idle = session
+        .as_ref()
+        .is_some_and(|s| timeout > 0 && s.last_used.elapsed() > Duration::from_secs(timeout));
+    if idle {
+        end_session(&mut session);
+    }
+    idle
+}
+
+pub struct VaultStore;
+
+impl SecretStore for VaultStore {
+    fn get(&self, name: &str) -> Result<Option<String>, ConfigError> {
+        let key = session_key()?;
+        let Some(sealed) = load()?.and_then(|mut v| v.entries.remove(name)) else {
+            return Ok(None);
+        };
+
+        let plaintext = open(&key, &sealed)
+            .ok_or_else(|| ConfigError::Secret(t("vault.decrypt_failed").to_string()))?;
+        String::from_utf8(plaintext.to_vec())
+            .map(Some)
+            .map_err(|e| ConfigError::Secret(e.to_string()))
+    }
+
+    fn set(&self, name: &str, secret: &str) -> Result<(), ConfigError> {
+        let key = session_key()?;
+        let mut vault =
+            load()?.ok_or_else(|| ConfigError::Secret(t("vault.not_created").to_string()))?;
+        vault
+            .entries
+            .insert(name.to_string(), seal(&key, secret.as_bytes())?);
+        save(&vault)
+    }
+
+    fn delete(&self, name: &str) -> Result<(), ConfigError> {
+        let Some(mut vault) = load()? else {
+            return Ok(());
+        };
+        if vault.entries.remove(name).is_some() {
+            save(&vault)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,382 @@
+use crate::i18n::{t, tf};
+use crate::r2::types::{ObjectInfo, R2Error};
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FolderUploadOptions {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub follow_symlinks: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedUpload {
+    pub local_path: String,
+    pub key: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedDownload {
+    pub key: String,
+    pub local_path: String,
+    pub size: u64,
+}
+
+const RESERVED_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+fn compile(patterns: &[String]) -> Result<Vec<Pattern>, R2Error> {
+    patterns
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| {
+            Pattern::new(p.trim())
+                .map_err(|e| R2Error::InvalidInput(tf("file.invalid_pattern", &[p, &e])))
+        })
+        .collect()
+}
+
+// Object keys always use "/", whatever the local separator is
+fn relative_key(path: &Path) -> String {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().replace('\\', "/")),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub fn join_key(prefix: &str, relative: &str) -> String {
+    let prefix = prefix.replace('\\', "/");
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        relative.to_string()
+    } else {
+        format!("{}/{}", prefix, relative)
+    }
+}
+
+// Walks `root` and maps every selected file to a key under `prefix/<folder name>/`
+pub fn plan_folder_upload(
+    root: &Path,
+    prefix: &str,
+    options: &FolderUploadOptions,
+) -> Result<Vec<PlannedUpload>, R2Error> {
+    if !root.is_dir() {
+        return Err(R2Error::InvalidInput(tf(
+            "file.not_a_dir",
+            &[&root.display()],
+        )));
+    }
+
+    let include = compile(&options.include)?;
+    let exclude = compile(&options.exclude)?;
+    let folder_name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let base = join_key(prefix, &folder_name);
+
+    let walker = WalkDir::new(root)
+        .follow_links(options.follow_symlinks)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            let relative = relative_key(relative);
+            relative.is_empty() || !exclude.iter().any(|p| p.matches(&relative))
+        });
+
+    let mut planned = Vec::new();
+    for entry in walker {
+        let entry =
+            entry.map_err(|e| R2Error::IoError(format!("{}: {}", t("file.read_dir_failed"), e)))?;
+
+        // Without follow_symlinks, links are neither traversed nor uploaded
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = relative_key(entry.path().strip_prefix(root).unwrap_or(entry.path()));
+        if !include.is_empty() && !include.iter().any(|p| p.matches(&relative)) {
+            continue;
+        }
+
+        let size = entry
+            .metadata()
+            .map_err(|e| R2Error::IoError(format!("{}: {}", t("file.read_failed"), e)))?
+            .len();
+
+        planned.push(PlannedUpload {
+            local_path: entry.path().to_string_lossy().to_string(),
+            key: join_key(&base, &relative),
+            size,
+        });
+    }
+
+    Ok(planned)
+}
+
+// Turns one key segment into a file name that is legal on every platform and can't
+// climb out of the target directory. Windows rules are applied everywhere so a
+// download looks the same whichever machine it lands on.
+fn sanitize_component(segment: &str) -> String {
+    let replaced: String = segment
+        .chars()
+        .map(|c| {
+            if c.is_control() || RESERVED_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    // Windows drops trailing dots and spaces, which also turns "." and ".." into ""
+    let trimmed = replaced.trim_end_matches(['.', ' ']);
+    if trimmed.is_empty() {
+        return "_".repeat(replaced.len().max(1));
+    }
+
+    let stem = trimmed.split('.').next().unwrap_or(trimmed);
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        return format!("_{}", trimmed);
+    }
+
+    trimmed.to_string()
+}
+
+// Keys that differ only by case or by sanitized characters would overwrite each other,
+// and a file can't take a name already needed as a directory
+fn unique_path(path: PathBuf, taken: &mut HashSet<String>) -> PathBuf {
+    if taken.insert(path.to_string_lossy().to_lowercase()) {
+        return path;
+    }
+
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| taken.insert(candidate.to_string_lossy().to_lowercase()))
+        .unwrap()
+}
+
+// Maps every object under `prefix` to a file under `target/<prefix name>/`,
+// recreating the key hierarchy. Folder markers carry no data and are skipped.
+pub fn plan_prefix_download(
+    objects: &[ObjectInfo],
+    prefix: &str,
+    target: &Path,
+) -> Result<Vec<PlannedDownload>, R2Error> {
+    if !target.is_dir() {
+        return Err(R2Error::InvalidInput(tf(
+            "file.not_a_dir",
+            &[&target.display()],
+        )));
+    }
+
+    let prefix = prefix.trim_start_matches('/');
+    let folder_name = prefix
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or("");
+    let base = if folder_name.is_empty() {
+        target.to_path_buf()
+    } else {
+        target.join(sanitize_component(folder_name))
+    };
+
+    let mut files = Vec::new();
+    for object in objects {
+        if object.is_folder {
+            continue;
+        }
+
+        let relative = object.key.strip_prefix(prefix).unwrap_or(&object.key);
+        let segments: Vec<&str> = relative.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.is_empty() {
+            continue;
+        }
+
+        let path = segments.iter().fold(base.clone(), |path, segment| {
+            path.join(sanitize_component(segment))
+        });
+        files.push((object, path));
+    }
+
+    // Every directory the downloads need is claimed up front, so key `a` becomes `a (1)`
+    // rather than blocking `a/b`, whichever comes first
+    let mut taken: HashSet<String> = files
+        .iter()
+        .flat_map(|(_, path)| path.ancestors().skip(1).take_while(|dir| *dir != base))
+        .map(|dir| dir.to_string_lossy().to_lowercase())
+        .collect();
+
+    Ok(files
+        .into_iter()
+        .map(|(object, path)| PlannedDownload {
+            key: object.key.clone(),
+            local_path: unique_path(path, &mut taken).to_string_lossy().to_string(),
+            size: object.size.max(0) as u64,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(key: &str) -> ObjectInfo {
+        ObjectInfo {
+            key: key.to_string(),
+            name: key.rsplit('/').next().unwrap_or_default().to_string(),
+            size: 1,
+            last_modified: String::new(),
+            is_folder: key.ends_with('/'),
+            etag: None,
+        }
+    }
+
+    fn plan(keys: &[&str], prefix: &str) -> (PathBuf, Vec<PathBuf>) {
+        let target = std::env::temp_dir();
+        let objects: Vec<ObjectInfo> = keys.iter().map(|key| object(key)).collect();
+        let planned = plan_prefix_download(&objects, prefix, &target).unwrap();
+        let paths = planned
+            .into_iter()
+            .map(|p| PathBuf::from(p.local_path))
+            .collect();
+        (target, paths)
+    }
+
+    fn stays_inside(target: &Path, path: &Path) -> bool {
+        path.starts_with(target)
+            && path
+                .strip_prefix(target)
+                .unwrap()
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+    }
+
+    #[test]
+    fn dot_segments_become_placeholders() {
+        assert_eq!(sanitize_component(".."), "__");
+        assert_eq!(sanitize_component("."), "_");
+        assert_eq!(sanitize_component("..."), "___");
+    }
+
+    #[test]
+    fn trailing_dots_and_spaces_are_trimmed() {
+        assert_eq!(sanitize_component("report."), "report");
+        assert_eq!(sanitize_component("report. . "), "report");
+        assert_eq!(sanitize_component(".env"), ".env");
+    }
+
+    #[test]
+    fn reserved_characters_are_replaced() {
+        assert_eq!(sanitize_component("a:b*c?.txt"), "a_b_c_.txt");
+        assert_eq!(sanitize_component("..\\..\\evil"), ".._.._evil");
+        assert_eq!(sanitize_component("tab\there"), "tab_here");
+    }
+
+    #[test]
+    fn reserved_names_are_prefixed() {
+        assert_eq!(sanitize_component("CON"), "_CON");
+        assert_eq!(sanitize_component("nul.txt"), "_nul.txt");
+        assert_eq!(sanitize_component("Com1.tar.gz"), "_Com1.tar.gz");
+        assert_eq!(sanitize_component("CONSOLE"), "CONSOLE");
+    }
+
+    #[test]
+    fn case_collisions_get_numbered() {
+        let mut taken = HashSet::new();
+        let first = unique_path(PathBuf::from("dir/Readme.md"), &mut taken);
+        let second = unique_path(PathBuf::from("dir/README.md"), &mut taken);
+        let third = unique_path(PathBuf::from("dir/readme.MD"), &mut taken);
+        assert_eq!(first, PathBuf::from("dir/Readme.md"));
+        assert_eq!(second, PathBuf::from("dir/README (1).md"));
+        assert_eq!(third, PathBuf::from("dir/readme (2).MD"));
+    }
+
+    #[test]
+    fn traversal_keys_stay_inside_the_target() {
+        let (target, paths) = plan(
+            &[
+                "docs/../../etc/passwd",
+                "docs/./a/../b",
+                "docs//\\..\\secret",
+                "docs/C:/windows",
+            ],
+            "docs/",
+        );
+        assert_eq!(paths.len(), 4);
+        for path in &paths {
+            assert!(stays_inside(&target, path), "{}", path.display());
+        }
+        assert_eq!(
+            paths[0],
+            target
+                .join("docs")
+                .join("__")
+                .join("__")
+                .join("etc")
+                .join("passwd")
+        );
+    }
+
+    #[test]
+    fn keys_outside_a_folder_land_in_the_target() {
+        let (target, paths) = plan(&["/etc/passwd", "../up", "photos/", "photos/a.jpg"], "");
+        assert_eq!(
+            paths,
+            vec![
+                target.join("etc").join("passwd"),
+                target.join("__").join("up"),
+                target.join("photos").join("a.jpg"),
+            ]
+        );
+    }
+
+    #[test]
+    fn files_give_way_to_directories() {
+        let (target, paths) = plan(&["data/a", "data/a/b", "data/A/c", "data/x/y"], "data/");
+        assert_eq!(
+            paths,
+            vec![
+                target.join("data").join("a (1)"),
+                target.join("data").join("a").join("b"),
+                target.join("data").join("A").join("c"),
+                target.join("data").join("x").join("y"),
+            ]
+        );
+    }
+
+    #[test]
+    fn colliding_keys_download_to_distinct_files() {
+        let (target, paths) = plan(&["docs/Readme.md", "docs/README.md", "docs/"], "docs");
+        assert_eq!(
+            paths,
+            vec![
+                target.join("docs").join("Readme.md"),
+                target.join("docs").join("README (1).md"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use super::progress::ProgressTracker;
+use crate::i18n::t;
+use crate::r2::control::{control_channel, ControlSignal, TransferControl};
+use crate::r2::types::R2Error;
+use crate::storage::journal::{JournalEntry, TransferKind};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{oneshot, watch};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferPhase {
+    Queued,
+    Running,
+    Paused,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferSnapshot {
+    pub id: String,
+    pub kind: TransferKind,
+    pub bucket_name: String,
+    pub key: String,
+    pub local_path: String,
+    pub priority: i32,
+    pub phase: TransferPhase,
+    pub bytes_transferred: u64,
+    pub total_bytes: u64,
+}
+
+struct ManagedTransfer {
+    kind: TransferKind,
+    bucket_name: String,
+    key: String,
+    local_path: String,
+    priority: i32,
+    // Insertion order, so equal priorities run first come, first served
+    sequence: u64,
+    // Holds a slot. A paused transfer gives its slot up.
+    running: bool,
+    // Has been handed a slot at least once, so resuming it must queue for one again
+    started: bool,
+    // Resumed while paused and waiting for a free slot before it is let go
+    resume_queued: bool,
+    sender: watch::Sender<ControlSignal>,
+    start: Option<oneshot::Sender<()>>,
+    progress: Option<Arc<ProgressTracker>>,
+}
+
+impl ManagedTransfer {
+    fn is_paused(&self) -> bool {
+        *self.sender.borrow() == ControlSignal::Pause
+    }
+}
+
+struct QueueState {
+    transfers: HashMap<String, ManagedTransfer>,
+    max_concurrent: usize,
+    next_sequence: u64,
+}
+
+pub struct TransferManager {
+    state: Mutex<QueueState>,
+}
+
+impl TransferManager {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                transfers: HashMap::new(),
+                max_concurrent: max_concurrent.max(1),
+                next_sequence: 0,
+            }),
+        }
+    }
+
+    // Registers the transfer; a second runner for the same entry is turned away
+    pub fn enqueue(&self, entry: &JournalEntry, priority: i32) -> Result<TransferControl, R2Error> {
+        let (sender, control) = control_channel();
+        let mut state = self.state.lock().unwrap();
+        if state.transfers.contains_key(&entry.id) {
+            return Err(R2Error::InvalidInput(
+                t("transfer.already_running").to_string(),
+            ));
+        }
+        let sequence = state.next_sequence;
+        state.next_sequence += 1;
+        state.transfers.insert(
+            entry.id.clone(),
+            ManagedTransfer {
+                kind: entry.kind,
+                bucket_name: entry.bucket_name.clone(),
+                key: entry.key.clone(),
+                local_path: entry.local_path.clone(),
+                priority,
+                sequence,
+                running: false,
+                started: false,
+                resume_queued: false,
+                sender,
+                start: None,
+                progress: None,
+            },
+        );
+        Ok(control)
+    }
+
+    // Waits until the queue hands this transfer a slot; fails if it is cancelled while queued
+    pub async fn wait_for_slot(&self, transfer_id: &str) -> Result<(), R2Error> {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+            let (sender, receiver) = oneshot::channel();
+            // A cancel may have come in while the runner was still preparing
+            let transfer = state
+                .transfers
+                .get_mut(transfer_id)
+                .filter(|t| *t.sender.borrow() != ControlSignal::Cancel)
+                .ok_or(R2Error::Cancelled)?;
+            transfer.start = Some(sender);
+            Self::dispatch(&mut state);
+            receiver
+        };
+
+        receiver.await.map_err(|_| R2Error::Cancelled)
+    }
+
+    pub fn attach_progress(&self, transfer_id: &str, progress: Arc<ProgressTracker>) {
+        if let Some(transfer) = self.state.lock().unwrap().transfers.get_mut(transfer_id) {
+            transfer.progress = Some(progress);
+        }
+    }
+
+    pub fn unregister(&self, transfer_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.transfers.remove(transfer_id);
+        Self::dispatch(&mut state);
+    }
+
+    pub fn is_active(&self, transfer_id: &str) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .transfers
+            .contains_key(transfer_id)
+    }
+
+    pub fn set_max_concurrent(&self, max_concurrent: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.max_concurrent = max_concurrent.max(1);
+        Self::dispatch(&mut state);
+    }
+
+    pub fn set_priority(&self, transfer_id: &str, priority: i32) -> Result<(), R2Error> {
+        let mut state = self.state.lock().unwrap();
+        let transfer = state
+            .transfers
+            .get_mut(transfer_id)
+            .ok_or(R2Error::TransferNotFound)?;
+        transfer.priority = priority;
+        Self::dispatch(&mut state);
+        Ok(())
+    }
+
+    pub fn cancel(&self, transfer_id: &str) -> Result<(), R2Error> {
+        self.signal(transfer_id, ControlSignal::Cancel)
+    }
+
+    pub fn pause(&self, transfer_id: &str) -> Result<(), R2Error> {
+        self.signal(transfer_id, ControlSignal::Pause)
+    }
+
+    pub fn resume(&self, transfer_id: &str) -> Result<(), R2Error> {
+        self.signal(transfer_id, ControlSignal::Run)
+    }
+
+    pub fn snapshot(&self) -> Vec<TransferSnapshot> {
+        let state = self.state.lock().unwrap();
+        let mut snapshots: Vec<(u64, TransferSnapshot)> = state
+            .transfers
+            .iter()
+            .map(|(id, t)| {
+                let (bytes_transferred, total_bytes) =
+                    t.progress.as_ref().map(|p| p.bytes()).unwrap_or_default();
+                let phase = if t.running {
+                    TransferPhase::Running
+                } else if t.is_paused() && !t.resume_queued {
+                    TransferPhase::Paused
+                } else {
+                    TransferPhase::Queued
+                };
+                let snapshot = TransferSnapshot {
+                    id: id.clone(),
+                    kind: t.kind,
+                    bucket_name: t.bucket_name.clone(),
+                    key: t.key.clone(),
+                    local_path: t.local_path.clone(),
+                    priority: t.priority,
+                    phase,
+                    bytes_transferred,
+                    total_bytes,
+                };
+                (t.sequence, snapshot)
+            })
+            .collect();
+
+        snapshots.sort_by_key(|(sequence, _)| *sequence);
+        snapshots.into_iter().map(|(_, s)| s).collect()
+    }
+
+    fn signal(&self, transfer_id: &str, signal: ControlSignal) -> Result<(), R2Error> {
+        let mut state = self.state.lock().unwrap();
+        let transfer = state
+            .transfers
+            .get_mut(transfer_id)
+            .ok_or(R2Error::TransferNotFound)?;
+
+        match signal {
+            // It gave up its slot when paused, so it waits for a free one before running on
+            ControlSignal::Run if transfer.started => {
+                transfer.resume_queued = transfer.is_paused();
+            }
+            _ => {
+                transfer.resume_queued = false;
+                if Self::send(transfer, signal) && signal == ControlSignal::Pause {
+                    transfer.running = false;
+                }
+            }
+        }
+
+        // Dropping the start sender wakes a queued transfer with a cancellation
+        if signal == ControlSignal::Cancel && !transfer.running {
+            transfer.start = None;
+        }
+
+        Self::dispatch(&mut state);
+        Ok(())
+    }
+
+    // A cancelled transfer can't be paused or resumed back to life
+    fn send(transfer: &ManagedTransfer, signal: ControlSignal) -> bool {
+        transfer.sender.send_if_modified(|current| {
+            if *current == ControlSignal::Cancel || *current == signal {
+                return false;
+            }
+            *current = signal;
+            true
+        })
+    }
+
+    // Start the highest-priority waiting transfers, new or resumed, while there are free
+    // slots. Paused transfers neither take a slot nor get started.
+    fn dispatch(state: &mut QueueState) {
+        let mut running = state.transfers.values().filter(|t| t.running).count();
+
+        while running < state.max_concurrent {
+            let next = state
+                .transfers
+                .values_mut()
+                .filter(|t| {
+                    !t.running && (t.resume_queued || (t.start.is_some() && !t.is_paused()))
+                })
+                .max_by_key(|t| (t.priority, std::cmp::Reverse(t.sequence)));
+
+            let Some(transfer) = next else {
+                break;
+            };
+
+            transfer.running = true;
+            transfer.started = true;
+            if std::mem::take(&mut transfer.resume_queued) {
+                Self::send(transfer, ControlSignal::Run);
+            }
+            if let Some(start) = transfer.start.take() {
+                let _ = start.send(());
+            }
+            running += 1;
+        }
+    }
+}
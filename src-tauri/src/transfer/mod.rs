@@ -0,0 +1,4 @@
+pub mod folder;
+pub mod manager;
+pub mod progress;
+pub mod runner;
@@ -0,0 +1,222 @@
+use crate::r2::types::{BatchPhase, BatchProgressSink, ProgressSink, R2Error};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+pub const PROGRESS_EVENT: &str = "transfer-progress";
+pub const FINISHED_EVENT: &str = "transfer-finished";
+pub const BATCH_PROGRESS_EVENT: &str = "batch-progress";
+
+const EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+// Weight of the latest sample in the smoothed throughput
+const RATE_SMOOTHING: f64 = 0.3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferProgress {
+    pub transfer_id: String,
+    pub bytes_transferred: u64,
+    pub total_bytes: u64,
+    pub bytes_per_second: f64,
+    pub eta_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FinishStatus {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferFinished {
+    pub transfer_id: String,
+    pub status: FinishStatus,
+    // Serialized like a command error, so background transfers can be told apart by code
+    pub error: Option<R2Error>,
+}
+
+pub fn emit_finished(app: &AppHandle, transfer_id: &str, result: &Result<(), R2Error>) {
+    let (status, error) = match result {
+        Ok(()) => (FinishStatus::Completed, None),
+        Err(R2Error::Cancelled) => (FinishStatus::Cancelled, None),
+        Err(e) => (FinishStatus::Failed, Some(e.clone())),
+    };
+
+    let _ = app.emit(
+        FINISHED_EVENT,
+        TransferFinished {
+            transfer_id: transfer_id.to_string(),
+            status,
+            error,
+        },
+    );
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchProgress {
+    pub operation_id: String,
+    pub phase: BatchPhase,
+    pub completed: u64,
+    pub total: u64,
+    pub done: bool,
+}
+
+// Emits `batch-progress` for moves and deletes that span many keys
+pub struct BatchTracker {
+    app: AppHandle,
+    operation_id: String,
+    state: Mutex<(BatchPhase, u64, u64, Instant)>,
+}
+
+impl BatchTracker {
+    pub fn new(app: AppHandle, operation_id: String) -> Self {
+        Self {
+            app,
+            operation_id,
+            state: Mutex::new((BatchPhase::Copy, 0, 0, Instant::now())),
+        }
+    }
+
+    pub fn finish(&self) {
+        self.emit(true, true);
+    }
+
+    fn emit(&self, force: bool, done: bool) {
+        let mut state = self.state.lock().unwrap();
+        let (phase, completed, total, last_emit) = &mut *state;
+        if !force && last_emit.elapsed() < EMIT_INTERVAL {
+            return;
+        }
+        *last_emit = Instant::now();
+
+        let _ = self.app.emit(
+            BATCH_PROGRESS_EVENT,
+            BatchProgress {
+                operation_id: self.operation_id.clone(),
+                phase: *phase,
+                completed: *completed,
+                total: *total,
+                done,
+            },
+        );
+    }
+}
+
+impl BatchProgressSink for BatchTracker {
+    fn start_phase(&self, phase: BatchPhase, total: u64) {
+        *self.state.lock().unwrap() = (phase, 0, total, Instant::now());
+        self.emit(true, false);
+    }
+
+    fn advance(&self, items: u64) {
+        self.state.lock().unwrap().1 += items;
+        self.emit(false, false);
+    }
+}
+
+struct RateState {
+    last_emit: Instant,
+    last_bytes: u64,
+    bytes_per_second: f64,
+}
+
+pub struct ProgressTracker {
+    app: AppHandle,
+    transfer_id: String,
+    total_bytes: AtomicU64,
+    transferred: AtomicU64,
+    rate: Mutex<RateState>,
+}
+
+impl ProgressTracker {
+    pub fn new(app: AppHandle, transfer_id: String) -> Self {
+        Self {
+            app,
+            transfer_id,
+            total_bytes: AtomicU64::new(0),
+            transferred: AtomicU64::new(0),
+            rate: Mutex::new(RateState {
+                last_emit: Instant::now(),
+                last_bytes: 0,
+                bytes_per_second: 0.0,
+            }),
+        }
+    }
+
+    pub fn bytes(&self) -> (u64, u64) {
+        (
+            self.transferred.load(Ordering::Relaxed),
+            self.total_bytes.load(Ordering::Relaxed),
+        )
+    }
+
+    pub fn finish(&self) {
+        self.emit(true);
+    }
+
+    fn emit(&self, force: bool) {
+        let mut rate = self.rate.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(rate.last_emit);
+
+        if !force && elapsed < EMIT_INTERVAL {
+            return;
+        }
+
+        let transferred = self.transferred.load(Ordering::Relaxed);
+        let total_bytes = self.total_bytes.load(Ordering::Relaxed);
+
+        if !elapsed.is_zero() {
+            let sample = transferred.saturating_sub(rate.last_bytes) as f64 / elapsed.as_secs_f64();
+            rate.bytes_per_second = if rate.last_bytes == 0 {
+                sample
+            } else {
+                RATE_SMOOTHING * sample + (1.0 - RATE_SMOOTHING) * rate.bytes_per_second
+            };
+        }
+        rate.last_emit = now;
+        rate.last_bytes = transferred;
+
+        let eta_seconds = if rate.bytes_per_second > 0.0 {
+            Some(
+                (total_bytes.saturating_sub(transferred) as f64 / rate.bytes_per_second).ceil()
+                    as u64,
+            )
+        } else {
+            None
+        };
+
+        let _ = self.app.emit(
+            PROGRESS_EVENT,
+            TransferProgress {
+                transfer_id: self.transfer_id.clone(),
+                bytes_transferred: transferred,
+                total_bytes,
+                bytes_per_second: rate.bytes_per_second,
+                eta_seconds,
+            },
+        );
+    }
+}
+
+impl ProgressSink for ProgressTracker {
+    fn set_total(&self, total_bytes: u64) {
+        self.total_bytes.store(total_bytes, Ordering::Relaxed);
+        self.emit(true);
+    }
+
+    fn advance(&self, bytes: u64) {
+        self.transferred.fetch_add(bytes, Ordering::Relaxed);
+        self.emit(false);
+    }
+
+    fn restart(&self) {
+        self.transferred.store(0, Ordering::Relaxed);
+        self.rate.lock().unwrap().last_bytes = 0;
+        self.emit(true);
+    }
+}
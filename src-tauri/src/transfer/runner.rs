@@ -0,0 +1,319 @@
+use super::manager::TransferManager;
+use super::progress::{emit_finished, ProgressTracker};
+use crate::i18n::{t, tf};
+use crate::r2::client::R2Client;
+use crate::r2::control::TransferHooks;
+use crate::r2::download::{temp_path_for, DownloadResume};
+use crate::r2::headers::UploadHeaders;
+use crate::r2::pool::ClientPool;
+use crate::r2::types::{CheckpointSink, R2Error, UploadedPart};
+use crate::r2::upload::MultipartResume;
+use crate::storage::config::ConfigStore;
+use crate::storage::journal::{JournalEntry, TransferJournal, TransferKind};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedTransfer {
+    pub transfer_id: String,
+    pub key: String,
+    pub local_path: String,
+    pub total_bytes: u64,
+}
+
+// A group of transfers queued together; the total drives the group's overall progress
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedBatch {
+    pub total_bytes: u64,
+    pub transfers: Vec<QueuedTransfer>,
+}
+
+struct JournalRecorder<'a> {
+    journal: &'a TransferJournal,
+    transfer_id: &'a str,
+}
+
+impl CheckpointSink for JournalRecorder<'_> {
+    fn multipart_started(&self, upload_id: &str, part_size: u64) {
+        let _ = self.journal.update(self.transfer_id, |entry| {
+            entry.upload_id = Some(upload_id.to_string());
+            entry.part_size = part_size;
+            entry.completed_parts.clear();
+            entry.bytes_transferred = 0;
+        });
+    }
+
+    fn part_uploaded(&self, part: &UploadedPart) {
+        let _ = self.journal.update(self.transfer_id, |entry| {
+            entry
+                .completed_parts
+                .retain(|p| p.part_number != part.part_number);
+            entry.completed_parts.push(part.clone());
+            entry.bytes_transferred =
+                (entry.completed_parts.len() as u64 * entry.part_size).min(entry.total_bytes);
+        });
+    }
+
+    fn download_started(&self, etag: Option<&str>, total_bytes: u64) {
+        let _ = self.journal.update(self.transfer_id, |entry| {
+            entry.etag = etag.map(|e| e.to_string());
+            entry.total_bytes = total_bytes;
+            entry.bytes_transferred = 0;
+        });
+    }
+
+    fn download_checkpoint(&self, bytes_written: u64) {
+        let _ = self.journal.update(self.transfer_id, |entry| {
+            entry.bytes_transferred = bytes_written;
+        });
+    }
+}
+
+fn modified_secs(metadata: &std::fs::Metadata) -> Option<i64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+}
+
+pub fn new_entry(
+    transfer_id: String,
+    kind: TransferKind,
+    account_id: String,
+    bucket_name: String,
+    key: String,
+    local_path: String,
+) -> Result<JournalEntry, R2Error> {
+    let (total_bytes, local_modified) = match kind {
+        TransferKind::Upload => {
+            let metadata = std::fs::metadata(&local_path)
+                .map_err(|e| R2Error::IoError(format!("{}: {}", t("file.open_failed"), e)))?;
+            if !metadata.is_file() {
+                return Err(R2Error::InvalidInput(tf("file.not_a_file", &[&local_path])));
+            }
+            (metadata.len(), modified_secs(&metadata))
+        }
+        TransferKind::Download => (0, None),
+    };
+
+    Ok(JournalEntry {
+        id: transfer_id,
+        kind,
+        account_id,
+        bucket_name,
+        key,
+        local_path,
+        total_bytes,
+        local_modified,
+        headers: Default::default(),
+        upload_id: None,
+        part_size: 0,
+        completed_parts: Vec::new(),
+        etag: None,
+        bytes_transferred: 0,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+// Start over if the source file was edited since the upload began
+async fn upload_resume(
+    client: &R2Client,
+    journal: &TransferJournal,
+    entry: &mut JournalEntry,
+) -> Result<Option<MultipartResume>, R2Error> {
+    let Some(upload_id) = entry.upload_id.clone() else {
+        return Ok(None);
+    };
+
+    let metadata =
+        std::fs::metadata(&entry.local_path).map_err(|e| R2Error::IoError(e.to_string()))?;
+
+    if metadata.len() == entry.total_bytes && modified_secs(&metadata) == entry.local_modified {
+        return Ok(Some(MultipartResume {
+            upload_id,
+            part_size: entry.part_size,
+        }));
+    }
+
+    let _ = client
+        .abort_multipart_upload(&entry.bucket_name, &entry.key, &upload_id)
+        .await;
+
+    entry.upload_id = None;
+    entry.completed_parts.clear();
+    entry.bytes_transferred = 0;
+    entry.total_bytes = metadata.len();
+    entry.local_modified = modified_secs(&metadata);
+    journal
+        .insert(entry.clone())
+        .map_err(|e| R2Error::IoError(e.to_string()))?;
+
+    Ok(None)
+}
+
+pub async fn run_transfer(
+    app: &AppHandle,
+    entry: JournalEntry,
+    priority: Option<i32>,
+) -> Result<(), R2Error> {
+    let transfer_id = entry.id.clone();
+    let result = execute_transfer(app, entry, priority).await;
+    emit_finished(app, &transfer_id, &result);
+    result
+}
+
+// Journals each transfer and runs it in the background; completion is reported
+// through the finished event
+pub fn spawn_transfers(
+    app: &AppHandle,
+    entries: Vec<JournalEntry>,
+) -> Result<QueuedBatch, R2Error> {
+    let journal = app.state::<TransferJournal>();
+    let mut queued = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        journal.insert(entry.clone())?;
+        queued.push(QueuedTransfer {
+            transfer_id: entry.id.clone(),
+            key: entry.key.clone(),
+            local_path: entry.local_path.clone(),
+            total_bytes: entry.total_bytes,
+        });
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = run_transfer(&app, entry, None).await;
+        });
+    }
+
+    Ok(QueuedBatch {
+        total_bytes: queued.iter().map(|t| t.total_bytes).sum(),
+        transfers: queued,
+    })
+}
+
+async fn execute_transfer(
+    app: &AppHandle,
+    mut entry: JournalEntry,
+    priority: Option<i32>,
+) -> Result<(), R2Error> {
+    let manager = app.state::<TransferManager>();
+    let journal = app.state::<TransferJournal>();
+
+    let settings = ConfigStore::new()
+        .and_then(|store| store.get_settings())
+        .map_err(|e| R2Error::IoError(e.to_string()))?;
+
+    // Without an explicit priority, uploads small enough for a single PUT skip ahead of big ones
+    let priority = priority.unwrap_or_else(|| {
+        let small = entry.kind == TransferKind::Upload
+            && !settings
+                .transfer
+                .multipart_config()
+                .use_multipart(entry.total_bytes);
+        i32::from(small)
+    });
+
+    // Registered before anything awaits, so a second resume is refused and a cancel goes
+    // through the manager instead of discarding files this runner is about to use
+    let control = manager.enqueue(&entry, priority)?;
+
+    let prepared = async {
+        let client = app.state::<ClientPool>().get(&entry.account_id).await?;
+        let multipart_resume = match entry.kind {
+            TransferKind::Upload => upload_resume(&client, &journal, &mut entry).await?,
+            TransferKind::Download => None,
+        };
+        Ok::<_, R2Error>((client, multipart_resume))
+    }
+    .await;
+    let (client, multipart_resume) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            manager.unregister(&entry.id);
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = manager.wait_for_slot(&entry.id).await {
+        manager.unregister(&entry.id);
+        let _ = discard_transfer(app, &entry).await;
+        return Err(e);
+    }
+
+    let path = Path::new(&entry.local_path);
+    let progress = Arc::new(ProgressTracker::new(app.clone(), entry.id.clone()));
+    manager.attach_progress(&entry.id, progress.clone());
+
+    let recorder = JournalRecorder {
+        journal: &journal,
+        transfer_id: &entry.id,
+    };
+    let hooks = TransferHooks {
+        progress: progress.as_ref(),
+        control: &control,
+        checkpoint: Some(&recorder),
+    };
+
+    let result = match entry.kind {
+        TransferKind::Upload => {
+            client
+                .upload_file(
+                    &entry.bucket_name,
+                    &entry.key,
+                    path,
+                    UploadHeaders::resolve(&entry.key, &settings.header_rules, &entry.headers),
+                    &settings.transfer.multipart_config(),
+                    multipart_resume,
+                    &hooks,
+                )
+                .await
+        }
+        TransferKind::Download => {
+            let resume = Some(DownloadResume {
+                etag: entry.etag.clone(),
+                offset: entry.bytes_transferred,
+            });
+            client
+                .download_file(&entry.bucket_name, &entry.key, path, resume, &hooks)
+                .await
+        }
+    };
+
+    manager.unregister(&entry.id);
+
+    // Failed transfers stay in the journal so they can be resumed later, as long as the
+    // error may clear on a retry. Otherwise their multipart upload or partial file is gone.
+    let resumable = result.as_ref().is_err_and(|e| e.is_retryable());
+    if !resumable {
+        let _ = journal.remove(&entry.id);
+    }
+
+    result?;
+
+    progress.finish();
+    Ok(())
+}
+
+// Clean up what an interrupted transfer left behind, on R2 and on disk
+pub async fn discard_transfer(app: &AppHandle, entry: &JournalEntry) -> Result<(), R2Error> {
+    match entry.kind {
+        TransferKind::Upload => {
+            if let Some(upload_id) = &entry.upload_id {
+                let client = app.state::<ClientPool>().get(&entry.account_id).await?;
+                client
+                    .abort_multipart_upload(&entry.bucket_name, &entry.key, upload_id)
+                    .await?;
+            }
+        }
+        TransferKind::Download => {
+            let _ = tokio::fs::remove_file(temp_path_for(Path::new(&entry.local_path))).await;
+        }
+    }
+
+    Ok(app.state::<TransferJournal>().remove(&entry.id)?)
+}
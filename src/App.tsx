@@ -2,6 +2,7 @@ import { useEffect } from "react";
 import { Layout } from "@/components/layout/Layout";
 import { ToastContainer } from "@/components/ui/Toast";
 import { TransferQueue } from "@/components/transfer/TransferQueue";
+import { VaultUnlockDialog } from "@/components/vault/VaultUnlockDialog";
 import { useThemeStore } from "@/stores/themeStore";
 
 function App() {
@@ -17,6 +18,7 @@ function App() {
     <>
       <Layout />
       <TransferQueue />
+      <VaultUnlockDialog />
       <ToastContainer />
     </>
   );
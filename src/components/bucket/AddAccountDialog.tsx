@@ -1,5 +1,5 @@
 import { useState } from "react";
-import { X, Eye, EyeOff, Loader2 } from "lucide-react";
+import { X, Eye, EyeOff, Loader2, ChevronDown, ChevronRight } from "lucide-react";
 import { useBucketStore } from "@/stores/bucketStore";
 import * as api from "@/lib/tauri";
 import { cn } from "@/lib/utils";
@@ -15,6 +15,11 @@ export function AddAccountDialog({ open, onOpenChange }: AddAccountDialogProps)
   const [accessKeyId, setAccessKeyId] = useState("");
   const [secretAccessKey, setSecretAccessKey] = useState("");
   const [showSecret, setShowSecret] = useState(false);
+  const [showAdvanced, setShowAdvanced] = useState(false);
+  const [jurisdiction, setJurisdiction] = useState<api.Jurisdiction>("default");
+  const [customEndpoint, setCustomEndpoint] = useState("");
+  const [forcePathStyle, setForcePathStyle] = useState(true);
+  const [region, setRegion] = useState("");
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState("");
 
@@ -24,21 +29,30 @@ export function AddAccountDialog({ open, onOpenChange }: AddAccountDialogProps)
     e.preventDefault();
     setError("");
 
-    if (!name || !accountId || !accessKeyId || !secretAccessKey) {
+    // A custom S3-compatible endpoint doesn't need a Cloudflare account ID
+    const hasCustomEndpoint = customEndpoint.trim() !== "";
+    if (!name || (!accountId && !hasCustomEndpoint) || !accessKeyId || !secretAccessKey) {
       setError("请填写所有字段");
       return;
     }
 
+    const endpoint: api.EndpointConfig = {
+      jurisdiction,
+      custom_endpoint: hasCustomEndpoint ? customEndpoint.trim() : null,
+      force_path_style: forcePathStyle,
+      region: region.trim() || null,
+    };
+
     setIsLoading(true);
 
     try {
       // Validate credentials first
-      await api.validateCredentials(accountId, accessKeyId, secretAccessKey);
+      await api.validateCredentials(accountId, accessKeyId, secretAccessKey, endpoint);
 
       const id = crypto.randomUUID();
 
       // Save to backend (secure storage)
-      await api.saveAccount(id, name, accountId, accessKeyId, secretAccessKey);
+      await api.saveAccount(id, name, accountId, accessKeyId, secretAccessKey, endpoint);
 
       // Add to local store
       addAccount({
@@ -54,9 +68,13 @@ export function AddAccountDialog({ open, onOpenChange }: AddAccountDialogProps)
       setAccountId("");
       setAccessKeyId("");
       setSecretAccessKey("");
+      setJurisdiction("default");
+      setCustomEndpoint("");
+      setForcePathStyle(true);
+      setRegion("");
       onOpenChange(false);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(api.errorMessage(err));
     } finally {
       setIsLoading(false);
     }
@@ -187,6 +205,90 @@ export function AddAccountDialog({ open, onOpenChange }: AddAccountDialogProps)
             </div>
           </div>
 
+          {/* Advanced: jurisdiction and S3-compatible endpoints */}
+          <div>
+            <button
+              type="button"
+              onClick={() => setShowAdvanced(!showAdvanced)}
+              className="flex items-center gap-1 text-sm text-muted-foreground hover:text-foreground"
+            >
+              {showAdvanced ? (
+                <ChevronDown className="h-4 w-4" />
+              ) : (
+                <ChevronRight className="h-4 w-4" />
+              )}
+              高级设置
+            </button>
+
+            {showAdvanced && (
+              <div className="mt-3 space-y-3">
+                <div>
+                  <label className="mb-1.5 block text-sm font-medium">管辖区</label>
+                  <select
+                    value={jurisdiction}
+                    onChange={(e) => setJurisdiction(e.target.value as api.Jurisdiction)}
+                    disabled={isLoading || customEndpoint.trim() !== ""}
+                    className={cn(
+                      "w-full rounded-md border border-input bg-background px-3 py-2",
+                      "text-sm focus:outline-none focus:ring-2 focus:ring-ring",
+                      "disabled:opacity-50"
+                    )}
+                  >
+                    <option value="default">默认</option>
+                    <option value="eu">欧盟 (EU)</option>
+                    <option value="fedramp">FedRAMP</option>
+                  </select>
+                </div>
+
+                <div>
+                  <label className="mb-1.5 block text-sm font-medium">
+                    自定义端点
+                  </label>
+                  <input
+                    type="text"
+                    value={customEndpoint}
+                    onChange={(e) => setCustomEndpoint(e.target.value)}
+                    placeholder="例如：http://localhost:9000（MinIO）"
+                    disabled={isLoading}
+                    className={cn(
+                      "w-full rounded-md border border-input bg-background px-3 py-2",
+                      "text-sm font-mono placeholder:text-muted-foreground",
+                      "focus:outline-none focus:ring-2 focus:ring-ring",
+                      "disabled:opacity-50"
+                    )}
+                  />
+                </div>
+
+                <div>
+                  <label className="mb-1.5 block text-sm font-medium">区域</label>
+                  <input
+                    type="text"
+                    value={region}
+                    onChange={(e) => setRegion(e.target.value)}
+                    placeholder="auto"
+                    disabled={isLoading}
+                    className={cn(
+                      "w-full rounded-md border border-input bg-background px-3 py-2",
+                      "text-sm font-mono placeholder:text-muted-foreground",
+                      "focus:outline-none focus:ring-2 focus:ring-ring",
+                      "disabled:opacity-50"
+                    )}
+                  />
+                </div>
+
+                <label className="flex items-center gap-2 text-sm">
+                  <input
+                    type="checkbox"
+                    checked={forcePathStyle}
+                    onChange={(e) => setForcePathStyle(e.target.checked)}
+                    disabled={isLoading}
+                  />
+                  使用路径样式访问 (Path-style)
+                </label>
+              </div>
+            )}
+          </div>
+
           {/* Error */}
           {error && (
             <p className="text-sm text-destructive">{error}</p>
@@ -6,6 +6,8 @@ import {
   Link,
   Eye,
   FolderOpen,
+  Pencil,
+  Info,
 } from "lucide-react";
 import { cn } from "@/lib/utils";
 
@@ -164,10 +166,20 @@ export function getFileActions(options: {
   onDownload: () => void;
   onCopyLink: () => void;
   onPreview: () => void;
+  onRename: () => void;
+  onProperties: () => void;
   onDelete: () => void;
 }): ContextMenuAction[] {
-  const { isFolder, onOpen, onDownload, onCopyLink, onPreview, onDelete } =
-    options;
+  const {
+    isFolder,
+    onOpen,
+    onDownload,
+    onCopyLink,
+    onPreview,
+    onRename,
+    onProperties,
+    onDelete,
+  } = options;
 
   return [
     {
@@ -181,7 +193,6 @@ export function getFileActions(options: {
       label: "下载",
       icon: Download,
       onClick: onDownload,
-      disabled: isFolder,
     },
     {
       id: "copy-link",
@@ -191,6 +202,19 @@ export function getFileActions(options: {
       disabled: isFolder,
       divider: true,
     },
+    {
+      id: "rename",
+      label: "重命名",
+      icon: Pencil,
+      onClick: onRename,
+    },
+    {
+      id: "properties",
+      label: "属性",
+      icon: Info,
+      onClick: onProperties,
+      disabled: isFolder,
+    },
     {
       id: "delete",
       label: "删除",
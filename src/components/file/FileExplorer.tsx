@@ -11,13 +11,24 @@ import { FileList } from "./FileList";
 import { EmptyState } from "./EmptyState";
 import { DropZone } from "./DropZone";
 import { FilePreview } from "./FilePreview";
+import { MetadataDialog } from "./MetadataDialog";
+import { InputDialog } from "@/components/ui/InputDialog";
 import { FileGridSkeleton, FileListSkeleton } from "@/components/ui/Skeleton";
 import { FolderOpen } from "lucide-react";
 
 export function FileExplorer() {
   const { selectedAccountId, selectedBucket, currentPath, navigateToFolder } = useBucketStore();
-  const { files, viewMode, isLoading, selectedFiles, selectAll, clearSelection } = useFileStore();
-  const { loadFiles, deleteFiles, getDownloadUrl } = useR2();
+  const {
+    files,
+    viewMode,
+    isLoading,
+    continuationToken,
+    isLoadingMore,
+    selectedFiles,
+    selectAll,
+    clearSelection,
+  } = useFileStore();
+  const { loadFiles, loadMoreFiles, deleteFiles, renameFile, getDownloadUrl } = useR2();
   const { downloadFile } = useTransfer();
   const { toast } = useToast();
 
@@ -29,6 +40,12 @@ export function FileExplorer() {
   } | null>(null);
   const [previewUrl, setPreviewUrl] = useState<string | null>(null);
 
+  // Rename state
+  const [renameTarget, setRenameTarget] = useState<{ key: string; name: string } | null>(null);
+
+  // Properties state
+  const [propertiesKey, setPropertiesKey] = useState<string | null>(null);
+
   // Load files when bucket or path changes
   useEffect(() => {
     if (selectedBucket) {
@@ -170,6 +187,8 @@ export function FileExplorer() {
                 onOpenFolder={handleOpenFolder}
                 onCopyLink={handleCopyLink}
                 onDownload={(key, name) => downloadFile(key, name)}
+                onRename={(key, name) => setRenameTarget({ key, name })}
+                onProperties={setPropertiesKey}
                 onDelete={(keys) => {
                   deleteFiles(keys);
                   clearSelection();
@@ -181,12 +200,27 @@ export function FileExplorer() {
                 onOpenFolder={handleOpenFolder}
                 onCopyLink={handleCopyLink}
                 onDownload={(key, name) => downloadFile(key, name)}
+                onRename={(key, name) => setRenameTarget({ key, name })}
+                onProperties={setPropertiesKey}
                 onDelete={(keys) => {
                   deleteFiles(keys);
                   clearSelection();
                 }}
               />
             )}
+
+            {/* Large folders are listed a page at a time */}
+            {!isLoading && continuationToken && (
+              <div className="flex justify-center py-4">
+                <button
+                  onClick={loadMoreFiles}
+                  disabled={isLoadingMore}
+                  className="rounded-md border border-border px-4 py-1.5 text-sm text-muted-foreground hover:bg-accent hover:text-foreground disabled:opacity-50"
+                >
+                  {isLoadingMore ? "加载中..." : "加载更多"}
+                </button>
+              </div>
+            )}
           </div>
         </DropZone>
       </div>
@@ -200,6 +234,34 @@ export function FileExplorer() {
         previewUrl={previewUrl}
         onDownload={handleDownloadFromPreview}
       />
+
+      {/* Rename Dialog, remounted per target so it opens with the current name */}
+      {renameTarget && (
+        <InputDialog
+          key={renameTarget.key}
+          open
+          onOpenChange={(open) => !open && setRenameTarget(null)}
+          title="重命名"
+          placeholder="输入新名称"
+          confirmText="重命名"
+          defaultValue={renameTarget.name}
+          onConfirm={(newName) => {
+            renameFile(renameTarget.key, newName);
+            setRenameTarget(null);
+          }}
+        />
+      )}
+
+      {/* Properties Dialog */}
+      {propertiesKey && selectedAccountId && (
+        <MetadataDialog
+          key={propertiesKey}
+          accountId={selectedAccountId}
+          bucketName={selectedBucket}
+          objectKey={propertiesKey}
+          onOpenChange={(open) => !open && setPropertiesKey(null)}
+        />
+      )}
     </>
   );
 }
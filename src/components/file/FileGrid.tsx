@@ -18,6 +18,8 @@ interface FileGridProps {
   onOpenFolder: (name: string) => void;
   onCopyLink: (key: string) => void;
   onDownload: (key: string, name: string) => void;
+  onRename: (key: string, name: string) => void;
+  onProperties: (key: string) => void;
   onDelete: (keys: string[]) => void;
 }
 
@@ -26,6 +28,8 @@ export function FileGrid({
   onOpenFolder,
   onCopyLink,
   onDownload,
+  onRename,
+  onProperties,
   onDelete,
 }: FileGridProps) {
   const { files, selectedFiles, toggleFileSelection, searchQuery } = useFileStore();
@@ -63,6 +67,8 @@ export function FileGrid({
         onDownload: () => onDownload(targetData.key, targetData.name),
         onCopyLink: () => onCopyLink(targetData.key),
         onPreview: () => onPreview({ key: targetData.key, name: targetData.name, size: targetData.size }),
+        onRename: () => onRename(targetData.key, targetData.name),
+        onProperties: () => onProperties(targetData.key),
         onDelete: () => onDelete([targetData.key]),
       })
     : [];
@@ -20,6 +20,8 @@ interface FileListProps {
   onOpenFolder: (name: string) => void;
   onCopyLink: (key: string) => void;
   onDownload: (key: string, name: string) => void;
+  onRename: (key: string, name: string) => void;
+  onProperties: (key: string) => void;
   onDelete: (keys: string[]) => void;
 }
 
@@ -28,6 +30,8 @@ export function FileList({
   onOpenFolder,
   onCopyLink,
   onDownload,
+  onRename,
+  onProperties,
   onDelete,
 }: FileListProps) {
   const {
@@ -99,6 +103,8 @@ export function FileList({
         onDownload: () => onDownload(targetData.key, targetData.name),
         onCopyLink: () => onCopyLink(targetData.key),
         onPreview: () => onPreview({ key: targetData.key, name: targetData.name, size: targetData.size }),
+        onRename: () => onRename(targetData.key, targetData.name),
+        onProperties: () => onProperties(targetData.key),
         onDelete: () => onDelete([targetData.key]),
       })
     : [];
@@ -8,6 +8,7 @@ import {
   List,
   Search,
   FolderPlus,
+  FolderUp,
 } from "lucide-react";
 import { useFileStore, type ViewMode } from "@/stores/fileStore";
 import { useR2 } from "@/hooks/useR2";
@@ -31,7 +32,7 @@ export function FileToolbar() {
   } = useFileStore();
 
   const { loadFiles, createFolder, deleteFiles } = useR2();
-  const { uploadFiles, downloadFiles } = useTransfer();
+  const { uploadFiles, uploadFolder, downloadFiles } = useTransfer();
 
   const hasSelection = selectedFiles.size > 0;
   const selectedCount = selectedFiles.size;
@@ -56,7 +57,7 @@ export function FileToolbar() {
 
   const handleDownload = () => {
     const filesToDownload = files
-      .filter((f) => selectedFiles.has(f.key) && !f.isFolder)
+      .filter((f) => selectedFiles.has(f.key))
       .map((f) => ({ key: f.key, name: f.name }));
 
     if (filesToDownload.length > 0) {
@@ -70,6 +71,11 @@ export function FileToolbar() {
         {/* Left Actions */}
         <div className="flex items-center gap-1">
           <ToolbarButton icon={Upload} label="上传" onClick={handleUpload} />
+          <ToolbarButton
+            icon={FolderUp}
+            label="上传文件夹"
+            onClick={uploadFolder}
+          />
           <ToolbarButton
             icon={FolderPlus}
             label="新建文件夹"
@@ -0,0 +1,252 @@
+import { useEffect, useState } from "react";
+import { X, Loader2, Plus, Trash2 } from "lucide-react";
+import * as api from "@/lib/tauri";
+import { useToast } from "@/hooks/useToast";
+import { cn, formatBytes, formatDate } from "@/lib/utils";
+
+interface MetadataDialogProps {
+  accountId: string;
+  bucketName: string;
+  objectKey: string;
+  onOpenChange: (open: boolean) => void;
+}
+
+interface MetadataEntry {
+  name: string;
+  value: string;
+}
+
+const inputClass = cn(
+  "w-full rounded-md border border-input bg-background px-3 py-2",
+  "text-sm font-mono placeholder:text-muted-foreground",
+  "focus:outline-none focus:ring-2 focus:ring-ring",
+  "disabled:opacity-50"
+);
+
+const HEADER_FIELDS = [
+  { field: "content_type", label: "Content-Type", placeholder: "application/octet-stream" },
+  { field: "cache_control", label: "Cache-Control", placeholder: "public, max-age=31536000" },
+  { field: "content_disposition", label: "Content-Disposition", placeholder: "inline" },
+  { field: "content_encoding", label: "Content-Encoding", placeholder: "gzip" },
+] as const;
+
+type HeaderField = (typeof HEADER_FIELDS)[number]["field"];
+
+export function MetadataDialog({
+  accountId,
+  bucketName,
+  objectKey,
+  onOpenChange,
+}: MetadataDialogProps) {
+  const [info, setInfo] = useState<api.ObjectMetadata | null>(null);
+  const [headers, setHeaders] = useState<Record<HeaderField, string>>({
+    content_type: "",
+    cache_control: "",
+    content_disposition: "",
+    content_encoding: "",
+  });
+  const [entries, setEntries] = useState<MetadataEntry[]>([]);
+  const [isSaving, setIsSaving] = useState(false);
+  const [error, setError] = useState("");
+  const { toast } = useToast();
+
+  const applyInfo = (metadata: api.ObjectMetadata) => {
+    setInfo(metadata);
+    setHeaders({
+      content_type: metadata.content_type ?? "",
+      cache_control: metadata.cache_control ?? "",
+      content_disposition: metadata.content_disposition ?? "",
+      content_encoding: metadata.content_encoding ?? "",
+    });
+    setEntries(
+      Object.entries(metadata.metadata).map(([name, value]) => ({ name, value }))
+    );
+  };
+
+  useEffect(() => {
+    api
+      .headObject(accountId, bucketName, objectKey)
+      .then(applyInfo)
+      .catch((err) => setError(api.errorMessage(err)));
+  }, [accountId, bucketName, objectKey]);
+
+  const updateEntry = (index: number, patch: Partial<MetadataEntry>) => {
+    setEntries(entries.map((entry, i) => (i === index ? { ...entry, ...patch } : entry)));
+  };
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    setError("");
+
+    // S3 lowercases metadata names, so duplicates are checked the same way
+    const metadata: Record<string, string> = {};
+    for (const entry of entries) {
+      const name = entry.name.trim().toLowerCase();
+      if (!name) continue;
+      if (name in metadata) {
+        setError(`元数据名称重复: ${name}`);
+        return;
+      }
+      metadata[name] = entry.value;
+    }
+
+    setIsSaving(true);
+    try {
+      const updated = await api.updateObjectMetadata(accountId, bucketName, objectKey, {
+        ...headers,
+        metadata,
+      });
+      applyInfo(updated);
+      toast({ type: "success", message: "属性已更新" });
+      onOpenChange(false);
+    } catch (err) {
+      setError(api.errorMessage(err));
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div
+        className="absolute inset-0 bg-black/50 backdrop-blur-sm"
+        onClick={() => !isSaving && onOpenChange(false)}
+      />
+
+      {/* Dialog */}
+      <div className="relative w-full max-w-md rounded-lg border border-border bg-card p-6 shadow-xl">
+        {/* Close Button */}
+        <button
+          onClick={() => onOpenChange(false)}
+          disabled={isSaving}
+          className="absolute right-4 top-4 text-muted-foreground hover:text-foreground disabled:opacity-50"
+        >
+          <X className="h-4 w-4" />
+        </button>
+
+        {/* Title */}
+        <h2 className="mb-1 text-lg font-semibold">属性</h2>
+        <p className="mb-4 truncate font-mono text-xs text-muted-foreground" title={objectKey}>
+          {objectKey}
+        </p>
+
+        {!info && !error ? (
+          <div className="flex justify-center py-8">
+            <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+          </div>
+        ) : (
+          <form onSubmit={handleSubmit} className="space-y-4">
+            {/* Read-only details */}
+            {info && (
+              <dl className="grid grid-cols-[auto,1fr] gap-x-4 gap-y-1 text-sm">
+                <dt className="text-muted-foreground">大小</dt>
+                <dd>{formatBytes(info.size)}</dd>
+                <dt className="text-muted-foreground">修改时间</dt>
+                <dd>{info.last_modified ? formatDate(info.last_modified) : "-"}</dd>
+                <dt className="text-muted-foreground">ETag</dt>
+                <dd className="truncate font-mono text-xs leading-5">{info.etag ?? "-"}</dd>
+              </dl>
+            )}
+
+            {/* HTTP headers; clearing a field removes the header */}
+            {HEADER_FIELDS.map(({ field, label, placeholder }) => (
+              <div key={field}>
+                <label className="mb-1.5 block text-sm font-medium">{label}</label>
+                <input
+                  type="text"
+                  value={headers[field]}
+                  onChange={(e) => setHeaders({ ...headers, [field]: e.target.value })}
+                  placeholder={placeholder}
+                  disabled={isSaving || !info}
+                  className={inputClass}
+                />
+              </div>
+            ))}
+
+            {/* Custom metadata (x-amz-meta-*) */}
+            <div>
+              <div className="mb-1.5 flex items-center justify-between">
+                <label className="text-sm font-medium">自定义元数据</label>
+                <button
+                  type="button"
+                  onClick={() => setEntries([...entries, { name: "", value: "" }])}
+                  disabled={isSaving || !info}
+                  className="flex items-center gap-1 text-xs text-muted-foreground hover:text-foreground disabled:opacity-50"
+                >
+                  <Plus className="h-3 w-3" />
+                  添加
+                </button>
+              </div>
+              <div className="space-y-2">
+                {entries.map((entry, index) => (
+                  <div key={index} className="flex items-center gap-2">
+                    <input
+                      type="text"
+                      value={entry.name}
+                      onChange={(e) => updateEntry(index, { name: e.target.value })}
+                      placeholder="名称"
+                      disabled={isSaving}
+                      className={inputClass}
+                    />
+                    <input
+                      type="text"
+                      value={entry.value}
+                      onChange={(e) => updateEntry(index, { value: e.target.value })}
+                      placeholder="值"
+                      disabled={isSaving}
+                      className={inputClass}
+                    />
+                    <button
+                      type="button"
+                      onClick={() => setEntries(entries.filter((_, i) => i !== index))}
+                      disabled={isSaving}
+                      className="text-muted-foreground hover:text-destructive disabled:opacity-50"
+                    >
+                      <Trash2 className="h-4 w-4" />
+                    </button>
+                  </div>
+                ))}
+                {entries.length === 0 && (
+                  <p className="text-xs text-muted-foreground">无</p>
+                )}
+              </div>
+            </div>
+
+            {/* Error */}
+            {error && <p className="text-sm text-destructive">{error}</p>}
+
+            {/* Actions */}
+            <div className="flex justify-end gap-2 pt-2">
+              <button
+                type="button"
+                onClick={() => onOpenChange(false)}
+                disabled={isSaving}
+                className={cn(
+                  "rounded-md px-4 py-2 text-sm font-medium",
+                  "text-muted-foreground hover:bg-accent hover:text-foreground",
+                  "transition-colors disabled:opacity-50"
+                )}
+              >
+                取消
+              </button>
+              <button
+                type="submit"
+                disabled={isSaving || !info}
+                className={cn(
+                  "flex items-center gap-2 rounded-md px-4 py-2 text-sm font-medium",
+                  "bg-primary text-primary-foreground",
+                  "hover:bg-primary/90 transition-colors",
+                  "disabled:opacity-50 disabled:cursor-not-allowed"
+                )}
+              >
+                {isSaving && <Loader2 className="h-4 w-4 animate-spin" />}
+                {isSaving ? "保存中..." : "保存"}
+              </button>
+            </div>
+          </form>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -1,9 +1,10 @@
 import { useState, useEffect } from "react";
-import { Plus, Database, ChevronRight, Loader2 } from "lucide-react";
+import { Plus, Database, ChevronRight, Loader2, Trash2 } from "lucide-react";
 import { useBucketStore, type Bucket } from "@/stores/bucketStore";
 import { useR2 } from "@/hooks/useR2";
 import { cn } from "@/lib/utils";
 import { AddAccountDialog } from "@/components/bucket/AddAccountDialog";
+import { ConfirmDialog } from "@/components/ui/ConfirmDialog";
 
 export function Sidebar() {
   const [isAddAccountOpen, setIsAddAccountOpen] = useState(false);
@@ -109,6 +110,20 @@ function AccountItem({
   onSelectBucket,
 }: AccountItemProps) {
   const [isExpanded, setIsExpanded] = useState(true);
+  const [bucketToDelete, setBucketToDelete] = useState<string | null>(null);
+  const [isDeleting, setIsDeleting] = useState(false);
+  const { deleteBucket } = useR2();
+
+  const handleDeleteBucket = async () => {
+    if (!bucketToDelete) return;
+    setIsDeleting(true);
+    try {
+      await deleteBucket(bucketToDelete);
+    } finally {
+      setIsDeleting(false);
+      setBucketToDelete(null);
+    }
+  };
 
   // Auto expand when selected
   useEffect(() => {
@@ -159,6 +174,7 @@ function AccountItem({
               bucket={bucket}
               isSelected={selectedBucket === bucket.name}
               onSelect={() => onSelectBucket(bucket.name)}
+              onDelete={() => setBucketToDelete(bucket.name)}
             />
           ))}
           {buckets.length === 0 && (
@@ -168,6 +184,17 @@ function AccountItem({
           )}
         </div>
       )}
+
+      <ConfirmDialog
+        open={!!bucketToDelete}
+        onOpenChange={(open) => !open && !isDeleting && setBucketToDelete(null)}
+        title="删除存储桶"
+        description={`存储桶 "${bucketToDelete}" 及其中的所有对象都将被永久删除, 此操作无法撤销。`}
+        confirmText="删除"
+        variant="danger"
+        onConfirm={handleDeleteBucket}
+        isLoading={isDeleting}
+      />
     </div>
   );
 }
@@ -176,9 +203,10 @@ interface BucketItemProps {
   bucket: Bucket;
   isSelected: boolean;
   onSelect: () => void;
+  onDelete: () => void;
 }
 
-function BucketItem({ bucket, isSelected, onSelect }: BucketItemProps) {
+function BucketItem({ bucket, isSelected, onSelect, onDelete }: BucketItemProps) {
   // 为每个桶生成一个固定的渐变色
   const gradients = [
     "from-blue-500 to-cyan-500",
@@ -193,23 +221,32 @@ function BucketItem({ bucket, isSelected, onSelect }: BucketItemProps) {
     gradients.length;
 
   return (
-    <button
-      onClick={onSelect}
-      className={cn(
-        "flex w-full items-center gap-2 rounded-md px-2 py-1.5",
-        "text-sm transition-colors",
-        isSelected
-          ? "bg-primary/10 text-primary"
-          : "text-muted-foreground hover:bg-accent/50 hover:text-foreground"
-      )}
-    >
-      <div
+    <div className="group relative">
+      <button
+        onClick={onSelect}
         className={cn(
-          "h-3 w-3 rounded-sm bg-gradient-to-br",
-          gradients[gradientIndex]
+          "flex w-full items-center gap-2 rounded-md px-2 py-1.5",
+          "text-sm transition-colors",
+          isSelected
+            ? "bg-primary/10 text-primary"
+            : "text-muted-foreground hover:bg-accent/50 hover:text-foreground"
         )}
-      />
-      <span className="flex-1 truncate text-left">{bucket.name}</span>
-    </button>
+      >
+        <div
+          className={cn(
+            "h-3 w-3 rounded-sm bg-gradient-to-br",
+            gradients[gradientIndex]
+          )}
+        />
+        <span className="flex-1 truncate text-left">{bucket.name}</span>
+      </button>
+      <button
+        onClick={onDelete}
+        title="删除存储桶"
+        className="absolute right-1 top-1/2 -translate-y-1/2 rounded p-1 text-muted-foreground opacity-0 transition-opacity hover:text-destructive group-hover:opacity-100"
+      >
+        <Trash2 className="h-3.5 w-3.5" />
+      </button>
+    </div>
   );
 }
@@ -1,13 +1,34 @@
-import { Upload, Download, CheckCircle2, XCircle } from "lucide-react";
+import { useEffect, useState } from "react";
+import { Upload, Download, CheckCircle2, XCircle, FolderSync } from "lucide-react";
 import { useTransferStore } from "@/stores/transferStore";
 import { useFileStore } from "@/stores/fileStore";
 import { useBucketStore } from "@/stores/bucketStore";
 import { formatBytes } from "@/lib/utils";
+import * as api from "@/lib/tauri";
 
 export function StatusBar() {
   const { transfers } = useTransferStore();
   const { files, selectedFiles } = useFileStore();
   const { selectedBucket, currentPath } = useBucketStore();
+  const [batches, setBatches] = useState<Record<string, api.BatchProgress>>({});
+
+  // Folder moves and deletes report object counts while they run
+  useEffect(() => {
+    const unlisten = api.onBatchProgress((progress) => {
+      setBatches((current) => {
+        const next = { ...current };
+        if (progress.done) {
+          delete next[progress.operation_id];
+        } else {
+          next[progress.operation_id] = progress;
+        }
+        return next;
+      });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
 
   const activeUploads = transfers.filter((t) => t.status === "uploading").length;
   const activeDownloads = transfers.filter((t) => t.status === "downloading").length;
@@ -41,6 +62,14 @@ export function StatusBar() {
 
       {/* Right: Transfer status */}
       <div className="flex items-center gap-3">
+        {Object.values(batches).map((batch) => (
+          <div key={batch.operation_id} className="flex items-center gap-1">
+            <FolderSync className="h-3 w-3 text-amber-500" />
+            <span>
+              {batch.phase === "copy" ? "复制中" : "删除中"} {batch.completed}/{batch.total}
+            </span>
+          </div>
+        ))}
         {activeUploads > 0 && (
           <div className="flex items-center gap-1">
             <Upload className="h-3 w-3 text-blue-500" />
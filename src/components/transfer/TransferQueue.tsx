@@ -9,18 +9,138 @@ import {
   ChevronUp,
   ChevronDown,
   Trash2,
+  Pause,
+  Play,
 } from "lucide-react";
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { useTransferStore, type TransferItem } from "@/stores/transferStore";
-import { cn } from "@/lib/utils";
+import * as api from "@/lib/tauri";
+import { cn, formatBytes } from "@/lib/utils";
 
 export function TransferQueue() {
   const [isExpanded, setIsExpanded] = useState(true);
-  const { transfers, removeTransfer, clearCompleted } = useTransferStore();
+  const {
+    transfers,
+    removeTransfer,
+    clearCompleted,
+    applyProgress,
+    updateStatus,
+    cancelTransfer,
+    restoreTransfers,
+  } = useTransferStore();
+
+  useEffect(() => {
+    api
+      .listResumableTransfers()
+      .then((entries) =>
+        restoreTransfers(
+          entries.map((entry) => ({
+            id: entry.id,
+            fileName: entry.key.split("/").pop() || entry.key,
+            filePath: entry.local_path,
+            bucketName: entry.bucket_name,
+            objectKey: entry.key,
+            size: entry.total_bytes,
+            progress:
+              entry.total_bytes > 0
+                ? (entry.bytes_transferred / entry.total_bytes) * 100
+                : 0,
+            status: "paused",
+            type: entry.kind,
+            interrupted: true,
+            startedAt: new Date(entry.created_at),
+          }))
+        )
+      )
+      .catch(() => {});
+  }, [restoreTransfers]);
+
+  useEffect(() => {
+    const unlisten = api.onTransferProgress(applyProgress);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [applyProgress]);
+
+  // Folder transfers run in the background, so their outcome only arrives as an event
+  useEffect(() => {
+    const unlisten = api.onTransferFinished((finished) => {
+      if (finished.status === "cancelled") {
+        cancelTransfer(finished.transfer_id);
+      } else {
+        updateStatus(
+          finished.transfer_id,
+          finished.status,
+          finished.error ? api.errorMessage(finished.error) : undefined
+        );
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [cancelTransfer, updateStatus]);
 
   const activeTransfers = transfers.filter(
-    (t) => t.status === "uploading" || t.status === "downloading" || t.status === "pending"
+    (t) =>
+      t.status === "uploading" ||
+      t.status === "downloading" ||
+      t.status === "pending" ||
+      t.status === "paused"
   );
+
+  // Overall progress across everything still in flight, e.g. a whole folder
+  const overallTotal = activeTransfers.reduce((sum, t) => sum + t.size, 0);
+  const overallTransferred = activeTransfers.reduce(
+    (sum, t) => sum + (t.bytesTransferred ?? 0),
+    0
+  );
+
+  const handlePause = async (transfer: TransferItem) => {
+    await api.pauseTransfer(transfer.id);
+    updateStatus(transfer.id, "paused");
+  };
+
+  const handleResume = async (transfer: TransferItem) => {
+    const activeStatus = transfer.type === "upload" ? "uploading" : "downloading";
+
+    if (!transfer.interrupted) {
+      await api.resumeTransfer(transfer.id);
+      updateStatus(transfer.id, activeStatus);
+      return;
+    }
+
+    // Interrupted transfers run to completion inside the resume call
+    useTransferStore.setState((state) => ({
+      transfers: state.transfers.map((t) =>
+        t.id === transfer.id ? { ...t, interrupted: false } : t
+      ),
+    }));
+    updateStatus(transfer.id, activeStatus);
+    try {
+      await api.resumeTransfer(transfer.id);
+      updateStatus(transfer.id, "completed");
+    } catch (error) {
+      const current = useTransferStore
+        .getState()
+        .transfers.find((t) => t.id === transfer.id);
+      if (current?.status !== "cancelled") {
+        updateStatus(transfer.id, "failed", api.errorMessage(error));
+      }
+    }
+  };
+
+  const handleCancel = async (transfer: TransferItem) => {
+    cancelTransfer(transfer.id);
+    await api.cancelTransfer(transfer.id);
+  };
+
+  const handleRemove = (transfer: TransferItem) => {
+    removeTransfer(transfer.id);
+    // Failed transfers are kept for resuming; removing them discards that state
+    if (transfer.status === "failed") {
+      api.cancelTransfer(transfer.id).catch(() => {});
+    }
+  };
   const completedTransfers = transfers.filter(
     (t) => t.status === "completed" || t.status === "failed" || t.status === "cancelled"
   );
@@ -40,6 +160,11 @@ export function TransferQueue() {
             <span className="rounded-full bg-primary/10 px-2 py-0.5 text-xs text-primary">
               {activeTransfers.length} 进行中
             </span>
+            {overallTotal > 0 && (
+              <span className="text-xs text-muted-foreground">
+                {formatBytes(overallTransferred)} / {formatBytes(overallTotal)}
+              </span>
+            )}
           </div>
           {isExpanded ? (
             <ChevronDown className="h-4 w-4 text-muted-foreground" />
@@ -63,7 +188,9 @@ export function TransferQueue() {
                   <TransferItemRow
                     key={transfer.id}
                     transfer={transfer}
-                    onRemove={() => removeTransfer(transfer.id)}
+                    onPause={() => handlePause(transfer)}
+                    onResume={() => handleResume(transfer)}
+                    onRemove={() => handleCancel(transfer)}
                   />
                 ))}
 
@@ -86,7 +213,7 @@ export function TransferQueue() {
                       <TransferItemRow
                         key={transfer.id}
                         transfer={transfer}
-                        onRemove={() => removeTransfer(transfer.id)}
+                        onRemove={() => handleRemove(transfer)}
                       />
                     ))}
                   </>
@@ -102,14 +229,18 @@ export function TransferQueue() {
 
 interface TransferItemRowProps {
   transfer: TransferItem;
+  onPause?: () => void;
+  onResume?: () => void;
   onRemove: () => void;
 }
 
-function TransferItemRow({ transfer, onRemove }: TransferItemRowProps) {
+function TransferItemRow({ transfer, onPause, onResume, onRemove }: TransferItemRowProps) {
+  const isPaused = transfer.status === "paused";
   const isActive =
     transfer.status === "uploading" ||
     transfer.status === "downloading" ||
-    transfer.status === "pending";
+    transfer.status === "pending" ||
+    isPaused;
 
   return (
     <div className="flex items-center gap-3 border-t border-border px-4 py-2">
@@ -145,10 +276,25 @@ function TransferItemRow({ transfer, onRemove }: TransferItemRowProps) {
         <div className="flex items-center gap-2 text-xs text-muted-foreground">
           {isActive ? (
             <>
-              <Loader2 className="h-3 w-3 animate-spin" />
-              <span>
-                {transfer.type === "upload" ? "上传中" : "下载中"}...
-              </span>
+              {isPaused ? (
+                <span>
+                  {transfer.interrupted ? "未完成" : "已暂停"} {transfer.progress.toFixed(0)}%
+                </span>
+              ) : (
+                <>
+                  <Loader2 className="h-3 w-3 animate-spin" />
+                  <span>
+                    {transfer.type === "upload" ? "上传中" : "下载中"}{" "}
+                    {transfer.progress.toFixed(0)}%
+                  </span>
+                </>
+              )}
+              {!isPaused && !!transfer.bytesPerSecond && (
+                <span>{formatBytes(transfer.bytesPerSecond)}/s</span>
+              )}
+              {!isPaused && transfer.etaSeconds != null && (
+                <span>剩余 {formatDuration(transfer.etaSeconds)}</span>
+              )}
             </>
           ) : transfer.status === "completed" ? (
             <span>完成</span>
@@ -158,8 +304,26 @@ function TransferItemRow({ transfer, onRemove }: TransferItemRowProps) {
             <span>已取消</span>
           )}
         </div>
+        {isActive && (
+          <div className="mt-1 h-1 w-full overflow-hidden rounded-full bg-muted">
+            <div
+              className="h-full bg-primary transition-all"
+              style={{ width: `${transfer.progress}%` }}
+            />
+          </div>
+        )}
       </div>
 
+      {/* Pause / Resume Button */}
+      {isActive && (isPaused ? onResume : onPause) && (
+        <button
+          onClick={isPaused ? onResume : onPause}
+          className="text-muted-foreground hover:text-foreground"
+        >
+          {isPaused ? <Play className="h-4 w-4" /> : <Pause className="h-4 w-4" />}
+        </button>
+      )}
+
       {/* Remove Button */}
       <button
         onClick={onRemove}
@@ -170,3 +334,9 @@ function TransferItemRow({ transfer, onRemove }: TransferItemRowProps) {
     </div>
   );
 }
+
+function formatDuration(seconds: number): string {
+  if (seconds < 60) return `${seconds} 秒`;
+  if (seconds < 3600) return `${Math.ceil(seconds / 60)} 分钟`;
+  return `${(seconds / 3600).toFixed(1)} 小时`;
+}
@@ -14,6 +14,7 @@ interface InputDialogProps {
   onConfirm: (value: string) => void;
   isLoading?: boolean;
   defaultValue?: string;
+  inputType?: "text" | "password";
 }
 
 export function InputDialog({
@@ -27,13 +28,15 @@ export function InputDialog({
   onConfirm,
   isLoading = false,
   defaultValue = "",
+  inputType = "text",
 }: InputDialogProps) {
   const [value, setValue] = useState(defaultValue);
 
   const handleSubmit = (e: React.FormEvent) => {
     e.preventDefault();
     if (value.trim()) {
-      onConfirm(value.trim());
+      // Passwords are taken verbatim, surrounding spaces included
+      onConfirm(inputType === "password" ? value : value.trim());
       setValue("");
       onOpenChange(false);
     }
@@ -80,7 +83,7 @@ export function InputDialog({
           {/* Form */}
           <form onSubmit={handleSubmit}>
             <input
-              type="text"
+              type={inputType}
               value={value}
               onChange={(e) => setValue(e.target.value)}
               placeholder={placeholder}
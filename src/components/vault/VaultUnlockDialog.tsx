@@ -0,0 +1,73 @@
+import { useCallback, useEffect, useState } from "react";
+import { InputDialog } from "@/components/ui/InputDialog";
+import { useToast } from "@/hooks/useToast";
+import * as api from "@/lib/tauri";
+
+type VaultPrompt = "create" | "unlock" | null;
+
+// Asks for the master password whenever the vault backend is selected but locked
+export function VaultUnlockDialog() {
+  const [prompt, setPrompt] = useState<VaultPrompt>(null);
+  const [isLoading, setIsLoading] = useState(false);
+  const { toast } = useToast();
+
+  const checkVault = useCallback(async () => {
+    try {
+      const settings = await api.getSettings();
+      if (settings.security.secret_backend !== "vault") {
+        setPrompt(null);
+        return;
+      }
+
+      const status = await api.vaultStatus();
+      setPrompt(!status.initialized ? "create" : status.unlocked ? null : "unlock");
+    } catch {
+      setPrompt(null);
+    }
+  }, []);
+
+  // The vault auto-locks while idle, so look again whenever the window comes back
+  useEffect(() => {
+    checkVault();
+    window.addEventListener("focus", checkVault);
+    return () => window.removeEventListener("focus", checkVault);
+  }, [checkVault]);
+
+  const handleConfirm = async (password: string) => {
+    setIsLoading(true);
+    try {
+      if (prompt === "create") {
+        await api.createVault(password);
+      } else {
+        await api.unlockVault(password);
+      }
+      setPrompt(null);
+    } catch (error) {
+      toast({
+        type: "error",
+        message: `解锁失败: ${api.errorMessage(error)}`,
+      });
+      checkVault();
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  return (
+    <InputDialog
+      open={prompt !== null}
+      onOpenChange={(open) => !open && setPrompt(null)}
+      title={prompt === "create" ? "创建密码库" : "解锁密码库"}
+      description={
+        prompt === "create"
+          ? "设置一个主密码，用于加密保存账户凭证。忘记主密码将无法恢复凭证。"
+          : "输入主密码以解锁账户凭证"
+      }
+      placeholder="主密码"
+      confirmText={prompt === "create" ? "创建" : "解锁"}
+      inputType="password"
+      isLoading={isLoading}
+      onConfirm={handleConfirm}
+    />
+  );
+}
@@ -1,17 +1,29 @@
 import { useCallback } from "react";
 import { useBucketStore } from "@/stores/bucketStore";
-import { useFileStore } from "@/stores/fileStore";
+import { useFileStore, type FileItem } from "@/stores/fileStore";
 import * as api from "@/lib/tauri";
 import { useToast } from "./useToast";
 
+function toFileItem(o: api.ObjectInfo): FileItem {
+  return {
+    key: o.key,
+    name: o.name,
+    size: o.size,
+    lastModified: o.last_modified,
+    isFolder: o.is_folder,
+    etag: o.etag || undefined,
+  };
+}
+
 export function useR2() {
   const {
     selectedAccountId,
     selectedBucket,
     currentPath,
     setBuckets,
+    selectBucket,
   } = useBucketStore();
-  const { setFiles, setLoading } = useFileStore();
+  const { setFiles, appendFiles, setLoading, setLoadingMore, setSelection } = useFileStore();
   const { toast } = useToast();
 
   // Load buckets for an account
@@ -29,7 +41,7 @@ export function useR2() {
       } catch (error) {
         toast({
           type: "error",
-          message: `加载存储桶失败: ${error}`,
+          message: `加载存储桶失败: ${api.errorMessage(error)}`,
         });
       }
     },
@@ -42,32 +54,50 @@ export function useR2() {
 
     setLoading(true);
     try {
-      const objects = await api.listObjects(
+      const page = await api.listObjects(
         selectedAccountId,
         selectedBucket,
         currentPath || undefined
       );
 
-      setFiles(
-        objects.map((o) => ({
-          key: o.key,
-          name: o.name,
-          size: o.size,
-          lastModified: o.last_modified,
-          isFolder: o.is_folder,
-          etag: o.etag || undefined,
-        }))
-      );
+      setFiles(page.objects.map(toFileItem), page.continuation_token);
     } catch (error) {
       toast({
         type: "error",
-        message: `加载文件列表失败: ${error}`,
+        message: `加载文件列表失败: ${api.errorMessage(error)}`,
       });
     } finally {
       setLoading(false);
     }
   }, [selectedAccountId, selectedBucket, currentPath, setFiles, setLoading, toast]);
 
+  // Load the next page of the current path
+  const loadMoreFiles = useCallback(async () => {
+    const { continuationToken, isLoadingMore } = useFileStore.getState();
+    if (!selectedAccountId || !selectedBucket || !continuationToken || isLoadingMore) {
+      return;
+    }
+
+    setLoadingMore(true);
+    try {
+      const page = await api.listObjects(
+        selectedAccountId,
+        selectedBucket,
+        currentPath || undefined,
+        continuationToken
+      );
+
+      appendFiles(page.objects.map(toFileItem), page.continuation_token);
+    } catch (error) {
+      toast({
+        type: "error",
+        message: `加载文件列表失败: ${api.errorMessage(error)}`,
+      });
+    } finally {
+      setLoadingMore(false);
+    }
+  }, [selectedAccountId, selectedBucket, currentPath, appendFiles, setLoadingMore, toast]);
+
   // Create folder
   const createFolder = useCallback(
     async (folderName: string) => {
@@ -87,7 +117,7 @@ export function useR2() {
       } catch (error) {
         toast({
           type: "error",
-          message: `创建文件夹失败: ${error}`,
+          message: `创建文件夹失败: ${api.errorMessage(error)}`,
         });
       }
     },
@@ -99,21 +129,96 @@ export function useR2() {
     async (keys: string[]) => {
       if (!selectedAccountId || !selectedBucket) return;
 
+      // Folders are marker objects, so everything beneath them goes too
+      const folders = keys.filter((key) => key.endsWith("/"));
+      const objects = keys.filter((key) => !key.endsWith("/"));
+
+      try {
+        // Items that weren't fully deleted stay selected, so deleting again retries just those
+        const retry: string[] = [];
+        const failures: api.KeyError[] = [];
+
+        for (const prefix of folders) {
+          const result = await api.deletePrefix(
+            selectedAccountId,
+            selectedBucket,
+            prefix,
+            crypto.randomUUID()
+          );
+          if (result.failed.length > 0) {
+            retry.push(prefix);
+            failures.push(...result.failed);
+          }
+        }
+
+        if (objects.length === 1) {
+          await api.deleteObject(selectedAccountId, selectedBucket, objects[0]);
+        } else if (objects.length > 1) {
+          const result = await api.deleteObjects(selectedAccountId, selectedBucket, objects);
+          retry.push(...result.failed.map((f) => f.key));
+          failures.push(...result.failed);
+        }
+
+        await loadFiles();
+
+        if (failures.length > 0) {
+          setSelection(retry);
+          toast({
+            type: "warning",
+            message: `${failures.length} 个对象删除失败 (${failures[0].error.message}), 已保留选中以便重试`,
+          });
+        } else {
+          toast({
+            type: "success",
+            message: `已删除 ${keys.length} 个项目`,
+          });
+        }
+      } catch (error) {
+        toast({
+          type: "error",
+          message: `删除失败: ${api.errorMessage(error)}`,
+        });
+      }
+    },
+    [selectedAccountId, selectedBucket, loadFiles, setSelection, toast]
+  );
+
+  // Rename a file or folder in place (server-side copy, then delete)
+  const renameFile = useCallback(
+    async (key: string, newName: string) => {
+      if (!selectedAccountId || !selectedBucket) return;
+
       try {
-        if (keys.length === 1) {
-          await api.deleteObject(selectedAccountId, selectedBucket, keys[0]);
+        if (key.endsWith("/")) {
+          const parent = key.slice(0, key.slice(0, -1).lastIndexOf("/") + 1);
+          const result = await api.movePrefix(
+            selectedAccountId,
+            selectedBucket,
+            key,
+            `${parent}${newName}/`,
+            crypto.randomUUID()
+          );
+          if (result.failed.length > 0) {
+            // Running the rename again picks up whatever is left
+            toast({
+              type: "warning",
+              message: `已移动 ${result.moved} 个对象, ${result.failed.length} 个失败, 可重试`,
+            });
+            await loadFiles();
+            return;
+          }
         } else {
-          await api.deleteObjects(selectedAccountId, selectedBucket, keys);
+          await api.renameObject(selectedAccountId, selectedBucket, key, newName);
         }
         toast({
           type: "success",
-          message: `已删除 ${keys.length} 个项目`,
+          message: `已重命名为 "${newName}"`,
         });
         await loadFiles();
       } catch (error) {
         toast({
           type: "error",
-          message: `删除失败: ${error}`,
+          message: `重命名失败: ${api.errorMessage(error)}`,
         });
       }
     },
@@ -135,7 +240,7 @@ export function useR2() {
       } catch (error) {
         toast({
           type: "error",
-          message: `获取下载链接失败: ${error}`,
+          message: `获取下载链接失败: ${api.errorMessage(error)}`,
         });
         return null;
       }
@@ -145,11 +250,11 @@ export function useR2() {
 
   // Create bucket
   const createBucket = useCallback(
-    async (bucketName: string) => {
+    async (bucketName: string, options?: api.CreateBucketOptions) => {
       if (!selectedAccountId) return;
 
       try {
-        await api.createBucket(selectedAccountId, bucketName);
+        await api.createBucket(selectedAccountId, bucketName, options);
         toast({
           type: "success",
           message: `存储桶 "${bucketName}" 创建成功`,
@@ -158,19 +263,47 @@ export function useR2() {
       } catch (error) {
         toast({
           type: "error",
-          message: `创建存储桶失败: ${error}`,
+          message: `创建存储桶失败: ${api.errorMessage(error)}`,
         });
       }
     },
     [selectedAccountId, loadBuckets, toast]
   );
 
+  // Delete a bucket, emptying it first
+  const deleteBucket = useCallback(
+    async (bucketName: string) => {
+      if (!selectedAccountId) return;
+
+      try {
+        await api.deleteBucket(selectedAccountId, bucketName, true, crypto.randomUUID());
+        toast({
+          type: "success",
+          message: `存储桶 "${bucketName}" 已删除`,
+        });
+        if (selectedBucket === bucketName) {
+          selectBucket(null);
+        }
+        await loadBuckets(selectedAccountId);
+      } catch (error) {
+        toast({
+          type: "error",
+          message: `删除存储桶失败: ${api.errorMessage(error)}`,
+        });
+      }
+    },
+    [selectedAccountId, selectedBucket, selectBucket, loadBuckets, toast]
+  );
+
   return {
     loadBuckets,
     loadFiles,
+    loadMoreFiles,
     createFolder,
     deleteFiles,
+    renameFile,
     getDownloadUrl,
     createBucket,
+    deleteBucket,
   };
 }
@@ -3,11 +3,19 @@ import { open, save } from "@tauri-apps/plugin-dialog";
 import { useBucketStore } from "@/stores/bucketStore";
 import { useTransferStore } from "@/stores/transferStore";
 import * as api from "@/lib/tauri";
+import { formatBytes } from "@/lib/utils";
 import { useToast } from "./useToast";
 
+function isCancelled(transferId: string): boolean {
+  const transfer = useTransferStore
+    .getState()
+    .transfers.find((t) => t.id === transferId);
+  return transfer?.status === "cancelled";
+}
+
 export function useTransfer() {
   const { selectedAccountId, selectedBucket, currentPath } = useBucketStore();
-  const { addTransfer, updateStatus } = useTransferStore();
+  const { addTransfer, restoreTransfers, updateStatus } = useTransferStore();
   const { toast } = useToast();
 
   // Upload files
@@ -29,51 +37,154 @@ export function useTransfer() {
 
     const fileList = Array.isArray(files) ? files : [files];
 
-    for (const filePath of fileList) {
-      const fileName = filePath.split(/[/\\]/).pop() || "unknown";
-      const objectKey = currentPath ? `${currentPath}/${fileName}` : fileName;
+    // Hand every file to the backend queue at once; it decides how many run concurrently
+    await Promise.all(
+      fileList.map(async (filePath) => {
+        const fileName = filePath.split(/[/\\]/).pop() || "unknown";
+        const objectKey = currentPath ? `${currentPath}/${fileName}` : fileName;
 
-      const transferId = addTransfer({
-        fileName,
-        filePath,
-        bucketName: selectedBucket,
-        objectKey,
-        size: 0,
-        progress: 0,
-        status: "pending",
-        type: "upload",
+        const transferId = addTransfer({
+          fileName,
+          filePath,
+          bucketName: selectedBucket,
+          objectKey,
+          size: 0,
+          progress: 0,
+          status: "pending",
+          type: "upload",
+        });
+
+        try {
+          await api.uploadFile(
+            transferId,
+            selectedAccountId,
+            selectedBucket,
+            objectKey,
+            filePath
+          );
+
+          updateStatus(transferId, "completed");
+          toast({
+            type: "success",
+            message: `${fileName} 上传成功`,
+          });
+        } catch (error) {
+          if (isCancelled(transferId)) return;
+          updateStatus(transferId, "failed", api.errorMessage(error));
+          toast({
+            type: "error",
+            message: `${fileName} 上传失败: ${api.errorMessage(error)}`,
+          });
+        }
+      })
+    );
+  }, [selectedAccountId, selectedBucket, currentPath, addTransfer, updateStatus, toast]);
+
+  // Show transfers the backend queued on its own; they report back through events
+  const queueBatch = useCallback(
+    (batch: api.QueuedBatch, type: "upload" | "download") => {
+      if (!selectedBucket) return;
+      restoreTransfers(
+        batch.transfers.map((item) => ({
+          id: item.transfer_id,
+          fileName: item.key.split("/").pop() || item.key,
+          filePath: item.local_path,
+          bucketName: selectedBucket,
+          objectKey: item.key,
+          size: item.total_bytes,
+          progress: 0,
+          status: "pending",
+          type,
+          startedAt: new Date(),
+        }))
+      );
+    },
+    [selectedBucket, restoreTransfers]
+  );
+
+  // Upload a folder recursively; each file becomes its own queued transfer
+  const uploadFolder = useCallback(async () => {
+    if (!selectedAccountId || !selectedBucket) {
+      toast({
+        type: "error",
+        message: "请先选择一个存储桶",
       });
+      return;
+    }
 
-      try {
-        updateStatus(transferId, "uploading");
+    const folder = await open({
+      directory: true,
+      title: "选择要上传的文件夹",
+    });
 
-        await api.uploadFile(
+    if (!folder || Array.isArray(folder)) return;
+
+    try {
+      const batch = await api.uploadFolder(
+        selectedAccountId,
+        selectedBucket,
+        folder,
+        currentPath || undefined
+      );
+
+      queueBatch(batch, "upload");
+      toast({
+        type: "success",
+        message: `已添加 ${batch.transfers.length} 个文件到上传队列 (${formatBytes(batch.total_bytes)})`,
+      });
+    } catch (error) {
+      toast({
+        type: "error",
+        message: `文件夹上传失败: ${api.errorMessage(error)}`,
+      });
+    }
+  }, [selectedAccountId, selectedBucket, currentPath, queueBatch, toast]);
+
+  // Download everything under a folder prefix into a chosen directory
+  const downloadFolder = useCallback(
+    async (prefix: string) => {
+      if (!selectedAccountId || !selectedBucket) return;
+
+      const saveDir = await open({
+        directory: true,
+        title: "选择保存位置",
+      });
+
+      if (!saveDir || Array.isArray(saveDir)) return;
+
+      try {
+        const batch = await api.downloadFolder(
           selectedAccountId,
           selectedBucket,
-          objectKey,
-          filePath
+          prefix,
+          saveDir
         );
 
-        updateStatus(transferId, "completed");
+        queueBatch(batch, "download");
         toast({
           type: "success",
-          message: `${fileName} 上传成功`,
+          message: `已添加 ${batch.transfers.length} 个文件到下载队列 (${formatBytes(batch.total_bytes)})`,
         });
       } catch (error) {
-        updateStatus(transferId, "failed", String(error));
         toast({
           type: "error",
-          message: `${fileName} 上传失败: ${error}`,
+          message: `文件夹下载失败: ${api.errorMessage(error)}`,
         });
       }
-    }
-  }, [selectedAccountId, selectedBucket, currentPath, addTransfer, updateStatus, toast]);
+    },
+    [selectedAccountId, selectedBucket, queueBatch, toast]
+  );
 
   // Download file
   const downloadFile = useCallback(
     async (key: string, fileName: string) => {
       if (!selectedAccountId || !selectedBucket) return;
 
+      if (key.endsWith("/")) {
+        await downloadFolder(key);
+        return;
+      }
+
       const savePath = await save({
         title: "保存文件",
         defaultPath: fileName,
@@ -93,9 +204,8 @@ export function useTransfer() {
       });
 
       try {
-        updateStatus(transferId, "downloading");
-
         await api.downloadFile(
+          transferId,
           selectedAccountId,
           selectedBucket,
           key,
@@ -108,14 +218,15 @@ export function useTransfer() {
           message: `${fileName} 下载成功`,
         });
       } catch (error) {
-        updateStatus(transferId, "failed", String(error));
+        if (isCancelled(transferId)) return;
+        updateStatus(transferId, "failed", api.errorMessage(error));
         toast({
           type: "error",
-          message: `${fileName} 下载失败: ${error}`,
+          message: `${fileName} 下载失败: ${api.errorMessage(error)}`,
         });
       }
     },
-    [selectedAccountId, selectedBucket, addTransfer, updateStatus, toast]
+    [selectedAccountId, selectedBucket, downloadFolder, addTransfer, updateStatus, toast]
   );
 
   // Download multiple files
@@ -130,6 +241,8 @@ export function useTransfer() {
 
   return {
     uploadFiles,
+    uploadFolder,
+    downloadFolder,
     downloadFile,
     downloadFiles,
   };
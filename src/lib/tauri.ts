@@ -1,10 +1,31 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
 // Types
+export type Jurisdiction = "default" | "eu" | "fedramp";
+
+export interface EndpointConfig {
+  jurisdiction: Jurisdiction;
+  custom_endpoint: string | null;
+  force_path_style: boolean;
+  region: string | null;
+}
+
 export interface AccountInfo {
   id: string;
   name: string;
   account_id: string;
+  endpoint: EndpointConfig;
+}
+
+// R2 placement regions; a hint, not a guarantee
+export type LocationHint = "wnam" | "enam" | "weur" | "eeur" | "apac" | "oc";
+
+export interface CreateBucketOptions {
+  location_hint?: LocationHint | null;
+  // Omitted uses the account's jurisdiction. Buckets in another jurisdiction won't show up
+  // in this account's bucket list.
+  jurisdiction?: Jurisdiction | null;
 }
 
 export interface BucketInfo {
@@ -12,6 +33,46 @@ export interface BucketInfo {
   creation_date: string | null;
 }
 
+export type CorsMethod = "GET" | "PUT" | "POST" | "DELETE" | "HEAD";
+
+export interface CorsRule {
+  id?: string | null;
+  allowed_origins: string[];
+  allowed_methods: CorsMethod[];
+  allowed_headers: string[];
+  expose_headers: string[];
+  max_age_seconds?: number | null;
+}
+
+export interface LifecycleRule {
+  id: string;
+  enabled: boolean;
+  // Empty applies the rule to the whole bucket
+  prefix: string;
+  // Set at most one of expiration_days and expiration_date (YYYY-MM-DD, UTC)
+  expiration_days?: number | null;
+  expiration_date?: string | null;
+  abort_multipart_days?: number | null;
+}
+
+export interface LifecycleMatch {
+  key: string;
+  size: number;
+  last_modified: string;
+  expires_at: string | null;
+}
+
+export interface LifecyclePreview {
+  matched_objects: number;
+  matched_bytes: number;
+  // Already past the threshold, so removed on R2's next daily lifecycle run
+  expiring_objects: number;
+  expiring_bytes: number;
+  incomplete_uploads: number;
+  // At most 100 matches, soonest to expire first
+  sample: LifecycleMatch[];
+}
+
 export interface ObjectInfo {
   key: string;
   name: string;
@@ -21,13 +82,217 @@ export interface ObjectInfo {
   etag: string | null;
 }
 
+// Fields left out keep the source object's value
+export interface ObjectMetadata {
+  key: string;
+  size: number;
+  etag: string | null;
+  last_modified: string | null;
+  content_type: string | null;
+  cache_control: string | null;
+  content_disposition: string | null;
+  content_encoding: string | null;
+  metadata: Record<string, string>;
+}
+
+// Omitted fields keep their current value; an empty string removes the header
+export interface MetadataReplacement {
+  content_type?: string;
+  cache_control?: string;
+  content_disposition?: string;
+  content_encoding?: string;
+  metadata?: Record<string, string>;
+}
+
+export interface CopyOptions {
+  destBucket?: string;
+  replaceMetadata?: MetadataReplacement;
+  overwrite?: boolean;
+}
+
+export type BatchPhase = "copy" | "delete";
+
+export interface BatchProgress {
+  operation_id: string;
+  phase: BatchPhase;
+  completed: number;
+  total: number;
+  done: boolean;
+}
+
+export interface KeyError {
+  key: string;
+  error: CommandError;
+}
+
+export interface PrefixMoveResult {
+  moved: number;
+  failed: KeyError[];
+}
+
+export interface DeleteResult {
+  deleted: string[];
+  failed: KeyError[];
+}
+
+export interface PrefixDeleteResult {
+  deleted: number;
+  failed: KeyError[];
+}
+
+export interface ObjectPage {
+  objects: ObjectInfo[];
+  continuation_token: string | null;
+  has_more: boolean;
+}
+
+export interface TransferProgress {
+  transfer_id: string;
+  bytes_transferred: number;
+  total_bytes: number;
+  bytes_per_second: number;
+  eta_seconds: number | null;
+}
+
+export interface ResumableTransfer {
+  id: string;
+  kind: "upload" | "download";
+  account_id: string;
+  bucket_name: string;
+  key: string;
+  local_path: string;
+  total_bytes: number;
+  bytes_transferred: number;
+  created_at: string;
+}
+
+export interface TransferSnapshot {
+  id: string;
+  kind: "upload" | "download";
+  bucket_name: string;
+  key: string;
+  local_path: string;
+  priority: number;
+  phase: "queued" | "running" | "paused";
+  bytes_transferred: number;
+  total_bytes: number;
+}
+
+export interface TransferFinished {
+  transfer_id: string;
+  status: "completed" | "failed" | "cancelled";
+  error: CommandError | null;
+}
+
+export interface QueuedTransfer {
+  transfer_id: string;
+  key: string;
+  local_path: string;
+  total_bytes: number;
+}
+
+export interface QueuedBatch {
+  total_bytes: number;
+  transfers: QueuedTransfer[];
+}
+
+export interface FolderUploadOptions {
+  include?: string[];
+  exclude?: string[];
+  follow_symlinks?: boolean;
+}
+
+// Headers stored with an upload; a missing Content-Type is detected from the file.
+// An empty string unsets a value a matching rule would apply.
+export interface UploadHeaders {
+  content_type?: string;
+  cache_control?: string;
+  content_disposition?: string;
+  content_encoding?: string;
+  metadata?: Record<string, string>;
+}
+
+// Glob matched against the full key, e.g. "*.html" or "assets/**"; later rules win
+export interface HeaderRule {
+  pattern: string;
+  headers: UploadHeaders;
+}
+
+export interface TransferSettings {
+  max_concurrent_transfers: number;
+  max_concurrent_parts: number;
+  part_size: number;
+  multipart_threshold: number;
+}
+
+export type SecretBackend = "keyring" | "file" | "vault";
+
+export interface SecuritySettings {
+  secret_backend: SecretBackend | null;
+  vault_auto_lock_minutes: number;
+}
+
+export type RetryMode = "standard" | "adaptive";
+
+// Timeouts are in seconds; 0 disables one
+export interface NetworkSettings {
+  retry_mode: RetryMode;
+  max_attempts: number;
+  connect_timeout_secs: number;
+  read_timeout_secs: number;
+  operation_timeout_secs: number;
+  operation_attempt_timeout_secs: number;
+}
+
+export type Locale = "zh-CN" | "en-US";
+
+export interface MessageCatalog {
+  locale: Locale;
+  messages: Record<string, string>;
+}
+
+export interface AppSettings {
+  // null follows the system locale
+  locale: Locale | null;
+  transfer: TransferSettings;
+  network: NetworkSettings;
+  security: SecuritySettings;
+  header_rules: HeaderRule[];
+}
+
+export interface VaultStatus {
+  initialized: boolean;
+  unlocked: boolean;
+}
+
+// Shape of every error a command rejects with
+export interface CommandError {
+  code: string;
+  message: string;
+  http_status: number | null;
+  s3_code: string | null;
+  request_id: string | null;
+  retryable: boolean;
+}
+
+export function isCommandError(error: unknown): error is CommandError {
+  return typeof error === "object" && error !== null && "code" in error && "message" in error;
+}
+
+export function errorMessage(error: unknown): string {
+  if (isCommandError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+}
+
 // Account Commands
 export async function saveAccount(
   id: string,
   name: string,
   accountId: string,
   accessKeyId: string,
-  secretAccessKey: string
+  secretAccessKey: string,
+  endpoint?: EndpointConfig
 ): Promise<void> {
   return invoke("save_account", {
     id,
@@ -35,6 +300,7 @@ export async function saveAccount(
     accountId,
     accessKeyId,
     secretAccessKey,
+    endpoint,
   });
 }
 
@@ -49,12 +315,14 @@ export async function deleteAccount(id: string): Promise<void> {
 export async function validateCredentials(
   accountId: string,
   accessKeyId: string,
-  secretAccessKey: string
+  secretAccessKey: string,
+  endpoint?: EndpointConfig
 ): Promise<boolean> {
   return invoke("validate_credentials", {
     accountId,
     accessKeyId,
     secretAccessKey,
+    endpoint,
   });
 }
 
@@ -65,25 +333,123 @@ export async function listBuckets(accountId: string): Promise<BucketInfo[]> {
 
 export async function createBucket(
   accountId: string,
-  bucketName: string
+  bucketName: string,
+  options?: CreateBucketOptions
 ): Promise<void> {
-  return invoke("create_bucket", { accountId, bucketName });
+  return invoke("create_bucket", { accountId, bucketName, options });
+}
+
+// Rejects with invalid_input explaining which R2 naming rule the name breaks
+export async function validateBucketName(bucketName: string): Promise<void> {
+  return invoke("validate_bucket_name", { bucketName });
 }
 
+// With force, the bucket is emptied first; progress arrives via onBatchProgress
 export async function deleteBucket(
+  accountId: string,
+  bucketName: string,
+  force: boolean = false,
+  operationId?: string
+): Promise<void> {
+  return invoke("delete_bucket", { accountId, bucketName, force, operationId });
+}
+
+// A bucket without CORS configured resolves to an empty list
+export async function getBucketCors(
+  accountId: string,
+  bucketName: string
+): Promise<CorsRule[]> {
+  return invoke("get_bucket_cors", { accountId, bucketName });
+}
+
+// Replaces every rule; an empty list removes the configuration. Resolves to the saved rules.
+export async function putBucketCors(
+  accountId: string,
+  bucketName: string,
+  rules: CorsRule[]
+): Promise<CorsRule[]> {
+  return invoke("put_bucket_cors", { accountId, bucketName, rules });
+}
+
+export async function deleteBucketCors(
   accountId: string,
   bucketName: string
 ): Promise<void> {
-  return invoke("delete_bucket", { accountId, bucketName });
+  return invoke("delete_bucket_cors", { accountId, bucketName });
+}
+
+// Rejects with invalid_input naming the offending rule; resolves to the normalized rules
+export async function validateCorsRules(rules: CorsRule[]): Promise<CorsRule[]> {
+  return invoke("validate_cors_rules", { rules });
+}
+
+// Also accepts `aws s3api get-bucket-cors` output; nothing is applied until putBucketCors
+export async function importCorsRules(filePath: string): Promise<CorsRule[]> {
+  return invoke("import_cors_rules", { filePath });
+}
+
+export async function exportCorsRules(filePath: string, rules: CorsRule[]): Promise<void> {
+  return invoke("export_cors_rules", { filePath, rules });
+}
+
+export async function listLifecycleRules(
+  accountId: string,
+  bucketName: string
+): Promise<LifecycleRule[]> {
+  return invoke("list_lifecycle_rules", { accountId, bucketName });
+}
+
+// add, update and remove resolve to the bucket's full rule set afterwards
+export async function addLifecycleRule(
+  accountId: string,
+  bucketName: string,
+  rule: LifecycleRule
+): Promise<LifecycleRule[]> {
+  return invoke("add_lifecycle_rule", { accountId, bucketName, rule });
+}
+
+// ruleId names the rule to replace; rule.id may rename it
+export async function updateLifecycleRule(
+  accountId: string,
+  bucketName: string,
+  ruleId: string,
+  rule: LifecycleRule
+): Promise<LifecycleRule[]> {
+  return invoke("update_lifecycle_rule", { accountId, bucketName, ruleId, rule });
+}
+
+export async function removeLifecycleRule(
+  accountId: string,
+  bucketName: string,
+  ruleId: string
+): Promise<LifecycleRule[]> {
+  return invoke("remove_lifecycle_rule", { accountId, bucketName, ruleId });
+}
+
+// Works on unsaved rules too, so a rule can be checked before it is added
+export async function previewLifecycleRule(
+  accountId: string,
+  bucketName: string,
+  rule: LifecycleRule
+): Promise<LifecyclePreview> {
+  return invoke("preview_lifecycle_rule", { accountId, bucketName, rule });
 }
 
 // File Commands
 export async function listObjects(
   accountId: string,
   bucketName: string,
-  prefix?: string
-): Promise<ObjectInfo[]> {
-  return invoke("list_objects", { accountId, bucketName, prefix });
+  prefix?: string,
+  continuationToken?: string,
+  maxKeys?: number
+): Promise<ObjectPage> {
+  return invoke("list_objects", {
+    accountId,
+    bucketName,
+    prefix,
+    continuationToken,
+    maxKeys,
+  });
 }
 
 export async function deleteObject(
@@ -98,7 +464,7 @@ export async function deleteObjects(
   accountId: string,
   bucketName: string,
   keys: string[]
-): Promise<void> {
+): Promise<DeleteResult> {
   return invoke("delete_objects", { accountId, bucketName, keys });
 }
 
@@ -119,21 +485,228 @@ export async function getPresignedUrl(
   return invoke("get_presigned_url", { accountId, bucketName, key, expiresIn });
 }
 
+export async function copyObject(
+  accountId: string,
+  bucketName: string,
+  sourceKey: string,
+  destKey: string,
+  options: CopyOptions = {}
+): Promise<void> {
+  return invoke("copy_object", { accountId, bucketName, sourceKey, destKey, ...options });
+}
+
+export async function moveObject(
+  accountId: string,
+  bucketName: string,
+  sourceKey: string,
+  destKey: string,
+  options: CopyOptions = {}
+): Promise<void> {
+  return invoke("move_object", { accountId, bucketName, sourceKey, destKey, ...options });
+}
+
+// Resolves to the object's new key
+export async function renameObject(
+  accountId: string,
+  bucketName: string,
+  key: string,
+  newName: string
+): Promise<string> {
+  return invoke("rename_object", { accountId, bucketName, key, newName });
+}
+
+export async function headObject(
+  accountId: string,
+  bucketName: string,
+  key: string
+): Promise<ObjectMetadata> {
+  return invoke("head_object", { accountId, bucketName, key });
+}
+
+// Rewrites headers and custom metadata in place; resolves to the updated metadata
+export async function updateObjectMetadata(
+  accountId: string,
+  bucketName: string,
+  key: string,
+  replacement: MetadataReplacement
+): Promise<ObjectMetadata> {
+  return invoke("update_object_metadata", { accountId, bucketName, key, replacement });
+}
+
+// Moves every key under a folder; progress arrives via onBatchProgress under operationId
+export async function movePrefix(
+  accountId: string,
+  bucketName: string,
+  sourcePrefix: string,
+  destPrefix: string,
+  operationId: string,
+  destBucket?: string
+): Promise<PrefixMoveResult> {
+  return invoke("move_prefix", {
+    accountId,
+    bucketName,
+    sourcePrefix,
+    destPrefix,
+    operationId,
+    destBucket,
+  });
+}
+
+// Deletes a folder recursively; progress arrives via onBatchProgress under operationId
+export async function deletePrefix(
+  accountId: string,
+  bucketName: string,
+  prefix: string,
+  operationId: string
+): Promise<PrefixDeleteResult> {
+  return invoke("delete_prefix", { accountId, bucketName, prefix, operationId });
+}
+
 // Transfer Commands
 export async function uploadFile(
+  transferId: string,
   accountId: string,
   bucketName: string,
   key: string,
-  filePath: string
+  filePath: string,
+  priority?: number,
+  headers?: UploadHeaders
 ): Promise<void> {
-  return invoke("upload_file", { accountId, bucketName, key, filePath });
+  return invoke("upload_file", {
+    transferId,
+    accountId,
+    bucketName,
+    key,
+    filePath,
+    priority,
+    headers,
+  });
 }
 
 export async function downloadFile(
+  transferId: string,
   accountId: string,
   bucketName: string,
   key: string,
-  savePath: string
+  savePath: string,
+  priority?: number
+): Promise<void> {
+  return invoke("download_file", {
+    transferId,
+    accountId,
+    bucketName,
+    key,
+    savePath,
+    priority,
+  });
+}
+
+export async function uploadFolder(
+  accountId: string,
+  bucketName: string,
+  folderPath: string,
+  prefix?: string,
+  options?: FolderUploadOptions,
+  headers?: UploadHeaders
+): Promise<QueuedBatch> {
+  return invoke("upload_folder", {
+    accountId,
+    bucketName,
+    prefix,
+    folderPath,
+    options,
+    headers,
+  });
+}
+
+export async function downloadFolder(
+  accountId: string,
+  bucketName: string,
+  prefix: string,
+  saveDir: string
+): Promise<QueuedBatch> {
+  return invoke("download_folder", { accountId, bucketName, prefix, saveDir });
+}
+
+export async function cancelTransfer(transferId: string): Promise<void> {
+  return invoke("cancel_transfer", { transferId });
+}
+
+export async function pauseTransfer(transferId: string): Promise<void> {
+  return invoke("pause_transfer", { transferId });
+}
+
+export async function resumeTransfer(transferId: string): Promise<void> {
+  return invoke("resume_transfer", { transferId });
+}
+
+export async function listResumableTransfers(): Promise<ResumableTransfer[]> {
+  return invoke("list_resumable_transfers");
+}
+
+export async function setTransferPriority(
+  transferId: string,
+  priority: number
 ): Promise<void> {
-  return invoke("download_file", { accountId, bucketName, key, savePath });
+  return invoke("set_transfer_priority", { transferId, priority });
+}
+
+export async function listTransfers(): Promise<TransferSnapshot[]> {
+  return invoke("list_transfers");
+}
+
+// Settings Commands
+export async function getSettings(): Promise<AppSettings> {
+  return invoke("get_settings");
+}
+
+// Error labels are keyed `error.<code>`, matching CommandError.code
+export async function getMessages(): Promise<MessageCatalog> {
+  return invoke("get_messages");
+}
+
+export async function saveSettings(settings: AppSettings): Promise<void> {
+  return invoke("save_settings", { settings });
+}
+
+// Vault Commands
+export async function vaultStatus(): Promise<VaultStatus> {
+  return invoke("vault_status");
+}
+
+export async function createVault(password: string): Promise<void> {
+  return invoke("create_vault", { password });
+}
+
+export async function unlockVault(password: string): Promise<void> {
+  return invoke("unlock_vault", { password });
+}
+
+export async function lockVault(): Promise<void> {
+  return invoke("lock_vault");
+}
+
+// Transfer Events
+export function onTransferProgress(
+  handler: (progress: TransferProgress) => void
+): Promise<UnlistenFn> {
+  return listen<TransferProgress>("transfer-progress", (event) =>
+    handler(event.payload)
+  );
+}
+
+export function onBatchProgress(
+  handler: (progress: BatchProgress) => void
+): Promise<UnlistenFn> {
+  return listen<BatchProgress>("batch-progress", (event) =>
+    handler(event.payload)
+  );
+}
+
+export function onTransferFinished(
+  handler: (finished: TransferFinished) => void
+): Promise<UnlistenFn> {
+  return listen<TransferFinished>("transfer-finished", (event) =>
+    handler(event.payload)
+  );
 }
@@ -21,19 +21,25 @@ interface FileState {
   sortOrder: SortOrder;
   searchQuery: string;
   isLoading: boolean;
+  // Token for the next page of the current folder, null once everything is loaded
+  continuationToken: string | null;
+  isLoadingMore: boolean;
 
   // Actions
-  setFiles: (files: FileItem[]) => void;
+  setFiles: (files: FileItem[], continuationToken?: string | null) => void;
+  appendFiles: (files: FileItem[], continuationToken: string | null) => void;
   selectFile: (key: string) => void;
   deselectFile: (key: string) => void;
   toggleFileSelection: (key: string) => void;
   selectAll: () => void;
   clearSelection: () => void;
+  setSelection: (keys: string[]) => void;
   setViewMode: (mode: ViewMode) => void;
   setSortField: (field: SortField) => void;
   setSortOrder: (order: SortOrder) => void;
   setSearchQuery: (query: string) => void;
   setLoading: (loading: boolean) => void;
+  setLoadingMore: (loading: boolean) => void;
 }
 
 export const useFileStore = create<FileState>((set) => ({
@@ -44,8 +50,17 @@ export const useFileStore = create<FileState>((set) => ({
   sortOrder: "asc",
   searchQuery: "",
   isLoading: false,
+  continuationToken: null,
+  isLoadingMore: false,
 
-  setFiles: (files) => set({ files, selectedFiles: new Set() }),
+  setFiles: (files, continuationToken = null) =>
+    set({ files, continuationToken, selectedFiles: new Set() }),
+
+  appendFiles: (files, continuationToken) =>
+    set((state) => ({
+      files: [...state.files, ...files],
+      continuationToken,
+    })),
 
   selectFile: (key) =>
     set((state) => ({
@@ -77,6 +92,8 @@ export const useFileStore = create<FileState>((set) => ({
 
   clearSelection: () => set({ selectedFiles: new Set() }),
 
+  setSelection: (keys) => set({ selectedFiles: new Set(keys) }),
+
   setViewMode: (mode) => set({ viewMode: mode }),
 
   setSortField: (field) => set({ sortField: field }),
@@ -86,4 +103,6 @@ export const useFileStore = create<FileState>((set) => ({
   setSearchQuery: (query) => set({ searchQuery: query }),
 
   setLoading: (loading) => set({ isLoading: loading }),
+
+  setLoadingMore: (loading) => set({ isLoadingMore: loading }),
 }));
@@ -1,6 +1,14 @@
 import { create } from "zustand";
+import type { TransferProgress } from "@/lib/tauri";
 
-export type TransferStatus = "pending" | "uploading" | "downloading" | "completed" | "failed" | "cancelled";
+export type TransferStatus =
+  | "pending"
+  | "uploading"
+  | "downloading"
+  | "paused"
+  | "completed"
+  | "failed"
+  | "cancelled";
 
 export interface TransferItem {
   id: string;
@@ -10,9 +18,14 @@ export interface TransferItem {
   objectKey: string;
   size: number;
   progress: number;
+  bytesTransferred?: number;
+  bytesPerSecond?: number;
+  etaSeconds?: number | null;
   status: TransferStatus;
   type: "upload" | "download";
   error?: string;
+  // Left unfinished by a previous session; resuming restarts it from the journal
+  interrupted?: boolean;
   startedAt: Date;
   completedAt?: Date;
 }
@@ -24,7 +37,9 @@ interface TransferState {
 
   // Actions
   addTransfer: (transfer: Omit<TransferItem, "id" | "startedAt">) => string;
+  restoreTransfers: (transfers: TransferItem[]) => void;
   updateProgress: (id: string, progress: number) => void;
+  applyProgress: (event: TransferProgress) => void;
   updateStatus: (id: string, status: TransferStatus, error?: string) => void;
   removeTransfer: (id: string) => void;
   clearCompleted: () => void;
@@ -51,6 +66,14 @@ export const useTransferStore = create<TransferState>((set) => ({
     return id;
   },
 
+  restoreTransfers: (restored) =>
+    set((state) => ({
+      transfers: [
+        ...state.transfers,
+        ...restored.filter((r) => !state.transfers.some((t) => t.id === r.id)),
+      ],
+    })),
+
   updateProgress: (id, progress) =>
     set((state) => ({
       transfers: state.transfers.map((t) =>
@@ -58,6 +81,32 @@ export const useTransferStore = create<TransferState>((set) => ({
       ),
     })),
 
+  applyProgress: (event) =>
+    set((state) => ({
+      transfers: state.transfers.map((t) =>
+        t.id === event.transfer_id
+          ? {
+              ...t,
+              // The backend queue reports progress once a queued transfer starts
+              status:
+                t.status === "pending"
+                  ? t.type === "upload"
+                    ? "uploading"
+                    : "downloading"
+                  : t.status,
+              size: event.total_bytes,
+              bytesTransferred: event.bytes_transferred,
+              bytesPerSecond: event.bytes_per_second,
+              etaSeconds: event.eta_seconds,
+              progress:
+                event.total_bytes > 0
+                  ? Math.min(100, (event.bytes_transferred / event.total_bytes) * 100)
+                  : 0,
+            }
+          : t
+      ),
+    })),
+
   updateStatus: (id, status, error) =>
     set((state) => ({
       transfers: state.transfers.map((t) =>